@@ -1,11 +1,26 @@
 #![windows_subsystem = "windows"]
 
 use iced::{
-    Element, Task, Theme,
-    widget::{button, column},
+    Element, Size, Subscription, Task, Theme,
+    widget::{button, column, row, toggler},
 };
 use iced_charts::{
+    alert::{Alert, Condition, Trigger},
     candle::{Candle, generate_data},
+    drawing::{self, Drawing, Tool},
+    feed::{self, RandomWalk},
+    history::{History, Step},
+    hit::CandleHit,
+    layout::ChartLayout,
+    marker::{Marker, Shape},
+    menu,
+    order::OrderLine,
+    replay::{self, Replay},
+    resample::{Session, resample},
+    source::{self, DataSource, HistoryRequest, MemorySource},
+    style, svg,
+    time::{self, Interval},
+    viewport::ViewportManager,
     widget::CandleChart,
 };
 
@@ -13,6 +28,7 @@ fn main() -> iced::Result {
     tracing_subscriber::fmt::init();
 
     iced::application(ChartApp::new, ChartApp::update, ChartApp::view)
+        .subscription(ChartApp::subscription)
         .title(ChartApp::title)
         .theme(ChartApp::theme)
         .antialiasing(true)
@@ -22,20 +38,88 @@ fn main() -> iced::Result {
 #[derive(Debug, Clone)]
 enum Message {
     AddCandle,
+    ReplayToggled,
+    Replay(replay::Action),
+    LiveToggled(bool),
+    LiveUpdate(Result<Candle, String>),
+    IntervalSelected(Interval),
+    HistoryRequested(HistoryRequest),
+    HistoryLoaded(Result<Vec<Candle>, String>),
+    ViewportChanged(ViewportManager, Size),
+    ToolSelected(Tool),
+    Drawing(drawing::Action),
+    RayToggled,
+    MagnetToggled(bool),
+    SteppingToggled(bool),
+    History(Step),
+    OrderMoved(usize, f32),
+    AlertAdded,
+    AlertCreated(Alert),
+    AlertTriggered(Trigger),
+    ChartClicked(CandleHit),
+    ExportSvg,
+    Exported(Result<(), String>),
 }
 
 #[derive(Debug, Default)]
 struct ChartApp {
+    source: MemorySource,
     candles: Vec<Candle>,
+    loading: bool,
+    /// Whether the source ran out of older candles.
+    history_complete: bool,
+    /// Candle the simulated live feed started from.
+    live: Option<Candle>,
+    interval: Option<Interval>,
+    replay: Option<Replay>,
+    viewport: Option<(ViewportManager, Size)>,
+    layout: ChartLayout,
+    history: History,
+    /// Simulated fills shown on the first chart.
+    fills: Vec<Marker>,
+    /// Simulated position and its orders shown on the first chart.
+    orders: Vec<OrderLine>,
+    alerts: Vec<Alert>,
+    tool: Tool,
+    magnet: bool,
+    stepping: bool,
 }
 
 impl ChartApp {
     fn new() -> (Self, Task<Message>) {
-        (
-            Self {
-                candles: generate_data(),
-            },
-            Task::none(),
+        let source = MemorySource::new(generate_data());
+        let app = Self {
+            source,
+            candles: Vec::new(),
+            loading: true,
+            history_complete: false,
+            live: None,
+            interval: None,
+            replay: None,
+            viewport: None,
+            layout: ChartLayout::new(),
+            history: History::new(),
+            fills: Vec::new(),
+            orders: Vec::new(),
+            alerts: Vec::new(),
+            tool: Tool::Select,
+            magnet: false,
+            stepping: false,
+        };
+        let task = app.fetch(HistoryRequest {
+            before: None,
+            limit: 200,
+        });
+
+        (app, task)
+    }
+
+    fn fetch(&self, request: HistoryRequest) -> Task<Message> {
+        let history = self.source.history(request);
+
+        Task::perform(
+            async move { history.await.map_err(|error| error.to_string()) },
+            Message::HistoryLoaded,
         )
     }
 
@@ -47,36 +131,302 @@ impl ChartApp {
         Theme::Dark
     }
 
-    fn update(&mut self, message: self::Message) {
+    fn update(&mut self, message: self::Message) -> Task<Message> {
         match message {
             Message::AddCandle => {
                 if let Some(latest) = self.candles.first() {
-                    let open = latest.close;
-                    let close = open + (rand::random::<f32>() - 0.5) * 2000.0 * 2.0;
-                    let high = open.max(close) + rand::random::<f32>() * 500.0 * 2.0;
-                    let low = open.min(close) - rand::random::<f32>() * 500.0 * 2.0;
-
-                    let new_candle = Candle {
-                        open,
-                        high,
-                        low,
-                        close,
-                    };
-
+                    let new_candle = feed::random_candle(latest, latest.time + time::DAY, 0.02);
                     self.candles.insert(0, new_candle);
                 }
             }
+            Message::ReplayToggled => {
+                self.replay = match self.replay {
+                    Some(_) => None,
+                    None => Some(Replay::new()),
+                };
+            }
+            Message::Replay(replay::Action::Exit) => {
+                self.replay = None;
+            }
+            Message::Replay(action) => {
+                let len = self.candles().len();
+                if let Some(replay) = &mut self.replay {
+                    replay.perform(action, len);
+                }
+            }
+            Message::LiveToggled(live) => {
+                self.live = live.then(|| self.candles.first().copied()).flatten();
+            }
+            Message::LiveUpdate(Ok(candle)) => {
+                feed::apply(&mut self.candles, candle);
+            }
+            Message::LiveUpdate(Err(error)) => {
+                tracing::error!("live feed failed: {error}");
+                self.live = None;
+            }
+            Message::IntervalSelected(interval) => {
+                self.interval = Some(interval);
+            }
+            Message::HistoryRequested(request) => {
+                self.loading = true;
+                return self.fetch(request);
+            }
+            Message::HistoryLoaded(result) => {
+                self.loading = false;
+                match result {
+                    Ok(page) => {
+                        self.history_complete = page.is_empty();
+                        source::merge(&mut self.candles, page);
+                        if self.fills.is_empty() {
+                            self.fills = sample_fills(&self.candles);
+                            self.orders = sample_orders(&self.candles);
+                        }
+                    }
+                    Err(error) => tracing::error!("failed to load history: {error}"),
+                }
+            }
+            Message::ViewportChanged(viewport, size) => {
+                self.layout.viewport = Some(viewport.target());
+                self.viewport = Some((viewport, size));
+            }
+            Message::ToolSelected(tool) => {
+                self.tool = tool;
+            }
+            Message::Drawing(action) => {
+                if matches!(action, drawing::Action::Add(_)) {
+                    self.tool = Tool::Select;
+                }
+                self.history.perform(&mut self.layout, action);
+            }
+            Message::RayToggled => {
+                if let Some(index) = self.layout.drawings.selected()
+                    && let Some(Drawing::TrendLine(line)) = self.layout.drawings.get(index)
+                {
+                    let mut line = *line;
+                    line.extend_right = !line.extend_right;
+                    self.history.perform(
+                        &mut self.layout,
+                        drawing::Action::Replace(index, Drawing::TrendLine(line)),
+                    );
+                }
+            }
+            Message::MagnetToggled(magnet) => {
+                self.magnet = magnet;
+            }
+            Message::SteppingToggled(stepping) => {
+                self.stepping = stepping;
+            }
+            Message::History(step) => {
+                self.history.step(&mut self.layout, step);
+            }
+            Message::OrderMoved(index, price) => {
+                if let Some(line) = self.orders.get_mut(index) {
+                    line.price = price;
+                }
+            }
+            Message::AlertAdded => {
+                let drawings = &self.layout.drawings;
+                if let Some(alert) = drawings
+                    .selected()
+                    .and_then(|index| Alert::on(drawings, index, Condition::Cross))
+                {
+                    self.alerts.push(alert);
+                }
+            }
+            Message::AlertCreated(alert) => {
+                self.alerts.push(alert);
+            }
+            Message::AlertTriggered(trigger) => {
+                let name = self
+                    .alerts
+                    .iter()
+                    .find(|alert| alert.id() == trigger.alert)
+                    .and_then(|alert| alert.name.as_deref())
+                    .unwrap_or("Alert");
+                tracing::info!("{name} triggered at {}", trigger.price);
+            }
+            Message::ChartClicked(hit) => {
+                tracing::info!(
+                    "clicked {:?} at candle {:?}, price {:.1}",
+                    hit.target,
+                    hit.index,
+                    hit.price
+                );
+            }
+            Message::ExportSvg => {
+                let (viewport, size) = self
+                    .viewport
+                    .clone()
+                    .unwrap_or_else(|| (ViewportManager::new(), Size::new(1280.0, 720.0)));
+
+                let candles = self.candles();
+                let document = svg::Export::new(&candles, &viewport, style::default(&self.theme()))
+                    .overrides(self.layout.style)
+                    .drawings(&self.layout.drawings)
+                    .markers(&self.fills)
+                    .orders(&self.orders)
+                    .alerts(&self.alerts)
+                    .render(size);
+
+                return Task::perform(
+                    async move {
+                        tokio::fs::write("chart.svg", document)
+                            .await
+                            .map_err(|error| error.to_string())
+                    },
+                    Message::Exported,
+                );
+            }
+            Message::Exported(result) => {
+                if let Err(error) = result {
+                    tracing::error!("failed to export chart: {error}");
+                }
+            }
+        }
+
+        Task::none()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let live = match self.live {
+            Some(start) => feed::subscription(RandomWalk::new(start, Interval::Days(1)))
+                .map(Message::LiveUpdate),
+            None => Subscription::none(),
+        };
+
+        let replay = match &self.replay {
+            Some(replay) => replay.subscription().map(Message::Replay),
+            None => Subscription::none(),
+        };
+
+        Subscription::batch([live, replay])
+    }
+
+    /// The daily sample data, resampled to the selected interval.
+    fn candles(&self) -> Vec<Candle> {
+        match self.interval {
+            Some(interval) => resample(&self.candles, interval, Session::UTC),
+            None => self.candles.clone(),
         }
     }
 
     fn view(&self) -> Element<'_, self::Message> {
+        let candles = self.candles();
+        let interval = self.interval.unwrap_or(Interval::Days(1));
+
+        let mut chart = CandleChart::new(candles.clone())
+            .interval(interval)
+            .loading(self.loading)
+            .on_viewport_change(Message::ViewportChanged)
+            .layout(&self.layout)
+            .markers(&self.fills)
+            .orders(&self.orders)
+            .on_order_drag(Message::OrderMoved)
+            .alerts(&self.alerts)
+            .on_alert(Message::AlertTriggered)
+            .on_click(Message::ChartClicked)
+            .on_alert_add(Message::AlertCreated)
+            .context_menu(|_| menu::standard())
+            .tool(self.tool)
+            .magnet(self.magnet)
+            .stepping(self.stepping)
+            .on_drawing(Message::Drawing)
+            .on_history(Message::History);
+
+        if !self.history_complete {
+            chart = chart.on_history_request(Message::HistoryRequested);
+        }
+        if let Some(replay) = self.replay {
+            chart = chart.replay(replay).on_replay(Message::Replay);
+        }
+
         column![
-            CandleChart::new(self.candles.clone()),
-            CandleChart::new(self.candles.clone()),
-            button("Add").on_press(Message::AddCandle)
+            chart,
+            CandleChart::new(candles).interval(interval),
+            row![
+                button("Add").on_press(Message::AddCandle),
+                button("1D").on_press(Message::IntervalSelected(Interval::Days(1))),
+                button("1W").on_press(Message::IntervalSelected(Interval::Weeks(1))),
+                button("1M").on_press(Message::IntervalSelected(Interval::Months(1))),
+                button("Export SVG").on_press(Message::ExportSvg),
+                button("Replay").on_press(Message::ReplayToggled),
+                button("Select").on_press(Message::ToolSelected(Tool::Select)),
+                button("Trend").on_press(Message::ToolSelected(Tool::TrendLine)),
+                button("Zoom").on_press(Message::ToolSelected(Tool::Zoom)),
+                button("Ray").on_press(Message::RayToggled),
+                button("Alert").on_press(Message::AlertAdded),
+                button("Undo").on_press_maybe(
+                    self.history
+                        .can_undo()
+                        .then_some(Message::History(Step::Undo))
+                ),
+                button("Redo").on_press_maybe(
+                    self.history
+                        .can_redo()
+                        .then_some(Message::History(Step::Redo))
+                ),
+                button("Level").on_press(Message::ToolSelected(Tool::HorizontalLine)),
+                button("Date").on_press(Message::ToolSelected(Tool::VerticalLine)),
+                button("Price range").on_press(Message::ToolSelected(Tool::PriceRange)),
+                button("Date range").on_press(Message::ToolSelected(Tool::DateRange)),
+                button("Fib").on_press(Message::ToolSelected(Tool::FibRetracement)),
+                button("Fib ext").on_press(Message::ToolSelected(Tool::FibExtension)),
+                button("Fib zones").on_press(Message::ToolSelected(Tool::FibTimeZone)),
+                button("Note").on_press(Message::ToolSelected(Tool::Note)),
+                button("Arrow").on_press(Message::ToolSelected(Tool::Arrow)),
+                button("Callout").on_press(Message::ToolSelected(Tool::Callout)),
+                toggler(self.magnet)
+                    .label("Magnet")
+                    .on_toggle(Message::MagnetToggled),
+                toggler(self.stepping)
+                    .label("Step")
+                    .on_toggle(Message::SteppingToggled),
+                toggler(self.live.is_some())
+                    .label("Live")
+                    .on_toggle(Message::LiveToggled),
+            ]
+            .spacing(10)
         ]
         .spacing(10)
         .padding(10)
         .into()
     }
 }
+
+/// A buy and a sell every few candles, with an occasional second fill on
+/// the same bar.
+fn sample_fills(candles: &[Candle]) -> Vec<Marker> {
+    candles
+        .iter()
+        .step_by(12)
+        .enumerate()
+        .flat_map(|(i, candle)| {
+            let buy = Marker::buy(candle.time, candle.low).text("Long");
+            let sell = Marker::sell(candle.time + time::HOUR, candle.high).shape(Shape::Flag);
+
+            match i % 3 {
+                0 => vec![buy],
+                1 => vec![sell],
+                _ => vec![
+                    buy.shape(Shape::Circle),
+                    Marker::buy(candle.time, candle.close),
+                ],
+            }
+        })
+        .collect()
+}
+
+/// A long position from the newest close with a stop, a take-profit and a
+/// limit order to add to it.
+fn sample_orders(candles: &[Candle]) -> Vec<OrderLine> {
+    let Some(last) = candles.first() else {
+        return Vec::new();
+    };
+
+    vec![
+        OrderLine::position(last.close, 1.0),
+        OrderLine::stop(last.close * 0.95, -1.0),
+        OrderLine::take_profit(last.close * 1.1, -1.0),
+        OrderLine::limit(last.close * 0.98, 0.5),
+    ]
+}
@@ -0,0 +1,155 @@
+//! Building candles from a stream of trades.
+
+use std::hash::{Hash, Hasher};
+
+use crate::{candle::Candle, time::Interval};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trade {
+    /// Trade time in unix milliseconds.
+    pub time: i64,
+    pub price: f32,
+    pub size: f32,
+}
+
+/// What closes a bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bars {
+    /// Calendar aligned time buckets.
+    Time(Interval),
+    /// A fixed number of trades.
+    Tick(u32),
+    /// Traded size reaching the threshold.
+    Volume(f32),
+    /// Traded `price * size` reaching the threshold.
+    Dollar(f32),
+}
+
+impl Hash for Bars {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+
+        match self {
+            Bars::Time(interval) => interval.hash(state),
+            Bars::Tick(count) => count.hash(state),
+            Bars::Volume(threshold) | Bars::Dollar(threshold) => threshold.to_bits().hash(state),
+        }
+    }
+}
+
+/// How a trade changed the series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Update {
+    /// A new candle was inserted at the front.
+    Opened,
+    /// The candle at the given index, counted from the newest, was updated.
+    Updated(usize),
+    /// The trade was older than every loaded bar.
+    Ignored,
+}
+
+/// Incrementally turns trades into a newest-first candle series.
+///
+/// Threshold bars are closed by the trade that reaches the threshold, which
+/// is never split between two bars. Trades in the same millisecond as a
+/// closed bar still go into it, since candles are keyed by their time.
+#[derive(Debug, Clone)]
+pub struct Aggregator {
+    bars: Bars,
+    candles: Vec<Candle>,
+    /// Ticks, volume or dollar value accumulated by the last candle.
+    progress: f32,
+}
+
+impl Aggregator {
+    pub fn new(bars: Bars) -> Self {
+        Self {
+            bars,
+            candles: Vec::new(),
+            progress: 0.0,
+        }
+    }
+
+    pub fn bars(&self) -> Bars {
+        self.bars
+    }
+
+    pub fn candles(&self) -> &[Candle] {
+        &self.candles
+    }
+
+    pub fn into_candles(self) -> Vec<Candle> {
+        self.candles
+    }
+
+    pub fn extend(&mut self, trades: impl IntoIterator<Item = Trade>) {
+        for trade in trades {
+            self.push(trade);
+        }
+    }
+
+    pub fn push(&mut self, trade: Trade) -> Update {
+        match self.bars {
+            Bars::Time(interval) => self.push_timed(interval, trade),
+            Bars::Tick(count) => self.push_threshold(trade, 1.0, count as f32),
+            Bars::Volume(volume) => self.push_threshold(trade, trade.size, volume),
+            Bars::Dollar(value) => self.push_threshold(trade, trade.price * trade.size, value),
+        }
+    }
+
+    fn push_timed(&mut self, interval: Interval, trade: Trade) -> Update {
+        let start = interval.floor(trade.time);
+
+        if self.candles.first().is_none_or(|last| last.time < start) {
+            self.candles.insert(0, open(start, trade));
+            return Update::Opened;
+        }
+
+        match self.candles.iter().position(|candle| candle.time == start) {
+            Some(index) => {
+                apply(&mut self.candles[index], trade, index == 0);
+                Update::Updated(index)
+            }
+            None => Update::Ignored,
+        }
+    }
+
+    fn push_threshold(&mut self, trade: Trade, amount: f32, threshold: f32) -> Update {
+        match self.candles.first_mut() {
+            Some(last) if last.time > trade.time => Update::Ignored,
+            Some(last) if self.progress < threshold || last.time == trade.time => {
+                apply(last, trade, true);
+                self.progress += amount;
+                Update::Updated(0)
+            }
+            _ => {
+                self.candles.insert(0, open(trade.time, trade));
+                self.progress = amount;
+                Update::Opened
+            }
+        }
+    }
+}
+
+fn open(time: i64, trade: Trade) -> Candle {
+    Candle {
+        time,
+        open: trade.price,
+        high: trade.price,
+        low: trade.price,
+        close: trade.price,
+        volume: trade.size,
+    }
+}
+
+/// Folds `trade` into `candle`. Only the in-progress candle takes the trade
+/// price as its close; late trades for older bars just widen the range.
+fn apply(candle: &mut Candle, trade: Trade, is_last: bool) {
+    candle.high = candle.high.max(trade.price);
+    candle.low = candle.low.min(trade.price);
+    candle.volume += trade.size;
+
+    if is_last {
+        candle.close = trade.price;
+    }
+}
@@ -0,0 +1,230 @@
+//! Price alerts on horizontal lines, trend lines and price channels.
+
+use std::collections::HashMap;
+
+use crate::{
+    candle::Candle,
+    drawing::{Drawing, Drawings, TrendLine},
+    id::Id,
+};
+
+/// What the price has to do at a level to trigger an alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Condition {
+    /// The close crosses the level in either direction.
+    #[default]
+    Cross,
+    /// The close crosses the level from below.
+    CrossUp,
+    /// The close crosses the level from above.
+    CrossDown,
+    /// The range of the newest candle reaches the level.
+    Touch,
+    /// The close moves into a channel.
+    Enter,
+    /// The close moves out of a channel.
+    Exit,
+}
+
+/// Price level watched by an alert.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum Level {
+    Price(f32),
+    Line(TrendLine),
+    /// The prices between two levels.
+    Channel {
+        low: f32,
+        high: f32,
+    },
+    /// The current level of a drawing, following it as it is edited. The
+    /// alert stops once the drawing is removed.
+    Drawing(Id),
+}
+
+impl Level {
+    /// The level of a horizontal line, trend line or price range.
+    pub fn of(drawing: &Drawing) -> Option<Self> {
+        match drawing {
+            Drawing::HorizontalLine(anchor) => Some(Self::Price(anchor.price)),
+            Drawing::TrendLine(line) => Some(Self::Line(*line)),
+            Drawing::PriceRange(range) => Some(Self::Channel {
+                low: range.start.price.min(range.end.price),
+                high: range.start.price.max(range.end.price),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Lowest and highest price of the level at `time`, equal for lines.
+    /// Drawing levels are looked up in `drawings`.
+    pub fn bounds_at(
+        &self,
+        candles: &[Candle],
+        drawings: Option<&Drawings>,
+        time: i64,
+    ) -> Option<(f32, f32)> {
+        match self {
+            Self::Price(price) => Some((*price, *price)),
+            Self::Line(line) => line.price_at(candles, time).map(|price| (price, price)),
+            Self::Channel { low, high } => Some((*low, *high)),
+            Self::Drawing(id) => drawings
+                .and_then(|drawings| drawings.get(drawings.position(*id)?))
+                .and_then(Self::of)
+                .and_then(|level| level.bounds_at(candles, None, time)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Alert {
+    pub level: Level,
+    pub condition: Condition,
+    /// Name shown next to the level and carried by its triggers.
+    pub name: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    id: Id,
+}
+
+impl PartialEq for Alert {
+    fn eq(&self, other: &Self) -> bool {
+        self.level == other.level && self.condition == other.condition && self.name == other.name
+    }
+}
+
+impl Alert {
+    pub fn new(level: Level, condition: Condition) -> Self {
+        Self {
+            level,
+            condition,
+            name: None,
+            id: Id::unique(),
+        }
+    }
+
+    /// An alert on the drawing at `index`, if it is a level alerts can
+    /// watch.
+    pub fn on(drawings: &Drawings, index: usize, condition: Condition) -> Option<Self> {
+        drawings.get(index).and_then(Level::of)?;
+        drawings
+            .id(index)
+            .map(|id| Self::new(Level::Drawing(id), condition))
+    }
+
+    /// Identifies the alert in its [`Trigger`]s, kept by its clones.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Whether moving from `previous` to the close of `candle` meets the
+    /// condition against a level spanning `low` to `high`.
+    fn triggers(&self, previous: f32, candle: &Candle, (low, high): (f32, f32)) -> bool {
+        let close = candle.close;
+        let inside = |price: f32| (low..=high).contains(&price);
+        let up = |level: f32| previous < level && close >= level;
+        let down = |level: f32| previous > level && close <= level;
+
+        match self.condition {
+            Condition::Cross => up(low) || down(low) || up(high) || down(high),
+            Condition::CrossUp => up(low) || up(high),
+            Condition::CrossDown => down(low) || down(high),
+            Condition::Touch => candle.low <= high && candle.high >= low,
+            Condition::Enter => !inside(previous) && inside(close),
+            Condition::Exit => inside(previous) && !inside(close),
+        }
+    }
+}
+
+/// An alert met by the newest candle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trigger {
+    /// Id of the alert.
+    pub alert: Id,
+    /// Open time of the candle that met it.
+    pub time: i64,
+    pub price: f32,
+}
+
+/// Watches the newest candle for alerts, triggering each at most once per
+/// candle.
+#[derive(Debug, Clone, Default)]
+pub struct Monitor {
+    /// Time and close of the newest candle last checked.
+    last: Option<(i64, f32)>,
+    /// Time of the candle each alert last triggered on.
+    triggered: HashMap<Id, i64>,
+}
+
+impl Monitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks `alerts` against the newest of `candles` when it was appended
+    /// or updated since the last check. The first check only records it.
+    pub fn check(
+        &mut self,
+        alerts: &[Alert],
+        candles: &[Candle],
+        drawings: Option<&Drawings>,
+    ) -> Vec<Trigger> {
+        self.triggered
+            .retain(|id, _| alerts.iter().any(|alert| alert.id == *id));
+
+        let Some(newest) = candles.first() else {
+            return Vec::new();
+        };
+        let Some((time, close)) = self.last.replace((newest.time, newest.close)) else {
+            return Vec::new();
+        };
+        if time == newest.time && close == newest.close {
+            return Vec::new();
+        }
+        // A candle older than the last one seen is a reload, not a tick.
+        if newest.time < time {
+            return Vec::new();
+        }
+
+        let mut triggers = Vec::new();
+        for alert in alerts {
+            if self.triggered.get(&alert.id) == Some(&newest.time) {
+                continue;
+            }
+            let Some(bounds) = alert.level.bounds_at(candles, drawings, newest.time) else {
+                continue;
+            };
+
+            if alert.triggers(close, newest, bounds) {
+                self.triggered.insert(alert.id, newest.time);
+                triggers.push(Trigger {
+                    alert: alert.id,
+                    time: newest.time,
+                    price: newest.close,
+                });
+            }
+        }
+        triggers
+    }
+
+    /// Whether `alert` triggered on the newest candle checked.
+    pub fn is_triggered(&self, alert: &Alert) -> bool {
+        self.last
+            .is_some_and(|(time, _)| self.triggered.get(&alert.id) == Some(&time))
+    }
+}
@@ -0,0 +1,90 @@
+//! Configurable mouse gestures for panning, zooming and scaling.
+
+use iced::mouse;
+
+/// What scrolling the mouse wheel does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WheelAction {
+    /// Zooms around the newest candle at the right edge.
+    Zoom,
+    /// Zooms around the candle under the cursor.
+    ZoomAtCursor,
+    /// Pans through time.
+    Pan,
+    /// Stretches or compresses the prices.
+    ScalePrice,
+    Nothing,
+}
+
+/// The gestures of a chart, by default those of the mouse wheel with and
+/// without Ctrl, Shift and Alt and panning with the left button.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bindings {
+    pub wheel: WheelAction,
+    pub ctrl_wheel: WheelAction,
+    pub shift_wheel: WheelAction,
+    pub alt_wheel: WheelAction,
+    /// Reverses the direction of every wheel action.
+    pub invert_wheel: bool,
+    /// Button dragging the chart around. Other gestures keep the left
+    /// button; when the right button pans, the context menu opens on a right
+    /// click without a drag.
+    pub pan_button: mouse::Button,
+    /// Change of zoom per wheel line.
+    pub zoom_sensitivity: f32,
+    /// Smallest and largest horizontal zoom.
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    /// Change of the visible price range per wheel line when scaling prices.
+    pub price_sensitivity: f32,
+    /// Smallest and largest price range the wheel scales to, as a
+    /// [`Viewport::height`](crate::viewport::Viewport::height): half the
+    /// prices shown from the bottom to the top of the chart.
+    pub min_height: f32,
+    pub max_height: f32,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            wheel: WheelAction::Zoom,
+            ctrl_wheel: WheelAction::ZoomAtCursor,
+            shift_wheel: WheelAction::Pan,
+            alt_wheel: WheelAction::ScalePrice,
+            invert_wheel: false,
+            pan_button: mouse::Button::Left,
+            zoom_sensitivity: 0.1,
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+            price_sensitivity: 5000.0,
+            min_height: 1000.0,
+            max_height: f32::MAX,
+        }
+    }
+}
+
+impl Bindings {
+    /// Clamps `zoom` to the zoom limits.
+    pub fn clamp_zoom(&self, zoom: f32) -> f32 {
+        zoom.clamp(self.min_zoom, self.max_zoom)
+    }
+
+    /// Clamps `height` to the price range limits.
+    pub fn clamp_height(&self, height: f32) -> f32 {
+        height.clamp(self.min_height, self.max_height)
+    }
+
+    /// The wheel action with the given modifiers held, Alt first, then
+    /// Shift, then Ctrl.
+    pub fn wheel_action(&self, ctrl: bool, shift: bool, alt: bool) -> WheelAction {
+        if alt {
+            self.alt_wheel
+        } else if shift {
+            self.shift_wheel
+        } else if ctrl {
+            self.ctrl_wheel
+        } else {
+            self.wheel
+        }
+    }
+}
@@ -1,11 +1,65 @@
-#[derive(Debug, Clone, Copy)]
+use crate::time;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Candle {
+    /// Open time of the candle in unix milliseconds.
+    pub time: i64,
     pub open: f32,
     pub high: f32,
     pub low: f32,
     pub close: f32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub volume: f32,
+}
+
+/// Open time at a fractional `index` into a newest-first series, extrapolating
+/// past either end with the spacing of the outermost candles.
+pub fn time_at(candles: &[Candle], index: f32) -> Option<i64> {
+    let last = candles.len().checked_sub(1)?;
+    let step = |newer: usize| {
+        candles
+            .get(newer + 1)
+            .map_or(0.0, |older| (candles[newer].time - older.time) as f64)
+    };
+
+    let index = index as f64;
+    let time = if index <= 0.0 {
+        candles[0].time as f64 - index * step(0)
+    } else if index >= last as f64 {
+        candles[last].time as f64 - (index - last as f64) * step(last.saturating_sub(1))
+    } else {
+        let i = index.floor() as usize;
+        candles[i].time as f64 - (index - i as f64) * step(i)
+    };
+
+    Some(time.round() as i64)
 }
 
+/// Inverse of [`time_at`]: the fractional index at which `time` falls.
+pub fn index_at(candles: &[Candle], time: i64) -> Option<f32> {
+    let last = candles.len().checked_sub(1)?;
+    let span = |newer: usize| {
+        candles
+            .get(newer + 1)
+            .map_or(1, |older| (candles[newer].time - older.time).max(1)) as f64
+    };
+
+    let index = if time >= candles[0].time {
+        -((time - candles[0].time) as f64) / span(0)
+    } else if time <= candles[last].time {
+        last as f64 + (candles[last].time - time) as f64 / span(last.saturating_sub(1))
+    } else {
+        let older = candles.partition_point(|candle| candle.time > time);
+        older as f64 - (time - candles[older].time) as f64 / span(older - 1)
+    };
+
+    Some(index as f32)
+}
+
+/// Open time of the newest candle in [`DATA`], 2025-07-14 UTC.
+const DATA_END: i64 = 1_752_451_200_000;
+
 pub fn generate_data() -> Vec<Candle> {
     let size = DATA.len();
 
@@ -14,10 +68,12 @@ pub fn generate_data() -> Vec<Candle> {
         let candle = DATA[size - i - 1];
 
         candles.push(Candle {
+            time: DATA_END - i as i64 * time::DAY,
             open: candle[0],
             high: candle[1],
             low: candle[2],
             close: candle[3],
+            volume: 0.0,
         });
     }
 
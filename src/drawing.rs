@@ -0,0 +1,1041 @@
+//! Drawing tools anchored to data coordinates.
+
+use iced::{
+    Color, Point, Rectangle, Size, Vector,
+    advanced::mouse::{Click, click},
+    keyboard,
+    mouse::{self, Cursor},
+    widget::canvas,
+};
+
+use crate::{candle, id::Id, viewport::Projection};
+
+/// Distance in pixels within which a handle or a line is hit.
+const HIT_DISTANCE: f32 = 6.0;
+/// Shortest drag in pixels that creates a drawing instead of a click.
+const MIN_DRAG: f32 = 4.0;
+/// Length in pixels of an annotation arrow.
+const ARROW_LENGTH: f32 = 30.0;
+/// Offset in pixels from its anchor to the corner of a callout box.
+const CALLOUT_OFFSET: Vector = Vector::new(20.0, -30.0);
+/// Space in pixels between the text of an annotation and its box.
+const PADDING: f32 = 4.0;
+
+/// A point of a drawing in data coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Anchor {
+    pub time: i64,
+    pub price: f32,
+}
+
+impl Anchor {
+    pub fn new(time: i64, price: f32) -> Self {
+        Self { time, price }
+    }
+
+    /// Anchor under `point`, relative to the chart bounds.
+    pub fn at(projection: &Projection<'_>, point: Point) -> Option<Self> {
+        let (time, price) = projection.to_data(point)?;
+        Some(Self::new(time, price))
+    }
+
+    /// Anchor at the open, high, low or close of the candle under `point`,
+    /// whichever is closest to it on screen.
+    pub fn snapped(projection: &Projection<'_>, point: Point) -> Option<Self> {
+        let last = projection.candles.len().checked_sub(1)?;
+        let index = projection
+            .viewport
+            .index_at(point.x, &projection.bounds, projection.spacing)
+            .round()
+            .clamp(0.0, last as f32) as usize;
+        let candle = projection.candles[index];
+
+        let distance = |price: f32| {
+            let y = projection
+                .viewport
+                .transform(0.0, price, &projection.bounds)
+                .y;
+            (y - point.y).abs()
+        };
+        let price = [candle.open, candle.high, candle.low, candle.close]
+            .into_iter()
+            .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))?;
+
+        Some(Self::new(candle.time, price))
+    }
+
+    /// Position of the anchor relative to the chart bounds.
+    pub fn position(&self, projection: &Projection<'_>) -> Option<Point> {
+        projection.to_screen(self.time, self.price)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrendLine {
+    pub start: Anchor,
+    pub end: Anchor,
+    /// Extends the line past its left end to the edge of the chart.
+    pub extend_left: bool,
+    /// Extends the line past its right end to the edge of the chart.
+    pub extend_right: bool,
+}
+
+impl TrendLine {
+    pub fn new(start: Anchor, end: Anchor) -> Self {
+        Self {
+            start,
+            end,
+            extend_left: false,
+            extend_right: false,
+        }
+    }
+
+    /// The line on screen, left end first, stretched past the chart bounds
+    /// on the extended sides.
+    pub fn segment(&self, projection: &Projection<'_>) -> Option<(Point, Point)> {
+        let start = self.start.position(projection)?;
+        let end = self.end.position(projection)?;
+        let (mut left, mut right) = if start.x <= end.x {
+            (start, end)
+        } else {
+            (end, start)
+        };
+
+        let direction = right - left;
+        let length = left.distance(right);
+        if length > 0.0 {
+            let reach = (projection.bounds.width + projection.bounds.height) / length;
+            if self.extend_left {
+                left -= direction * reach;
+            }
+            if self.extend_right {
+                right += direction * reach;
+            }
+        }
+
+        Some((left, right))
+    }
+
+    /// Price of the line at `time`, or `None` past its ends on the sides it
+    /// does not extend to.
+    pub fn price_at(&self, candles: &[candle::Candle], time: i64) -> Option<f32> {
+        let start = candle::index_at(candles, self.start.time)?;
+        let end = candle::index_at(candles, self.end.time)?;
+        let index = candle::index_at(candles, time)?;
+        if start == end {
+            return None;
+        }
+
+        // Older candles have larger indices, so the left end is the larger.
+        let (left, right) = (start.max(end), start.min(end));
+        if (index > left && !self.extend_left) || (index < right && !self.extend_right) {
+            return None;
+        }
+
+        Some(
+            self.start.price
+                + (self.end.price - self.start.price) * (index - start) / (end - start),
+        )
+    }
+}
+
+/// Ratios of the Fibonacci retracement levels.
+pub const RETRACEMENT_LEVELS: [f32; 7] = [0.0, 0.236, 0.382, 0.5, 0.618, 0.786, 1.0];
+/// Ratios of the Fibonacci extension levels.
+pub const EXTENSION_LEVELS: [f32; 7] = [0.0, 0.618, 1.0, 1.272, 1.618, 2.0, 2.618];
+/// Multiples of the anchor distance at which Fibonacci time zones start.
+pub const TIME_ZONE_LEVELS: [f32; 10] = [0.0, 1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 34.0, 55.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FibonacciKind {
+    /// Price levels between the anchors, measured back from the end.
+    Retracement,
+    /// Price levels measured from the start, reaching past the end.
+    Extension,
+    /// Vertical lines at multiples of the number of bars between the anchors.
+    TimeZone,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Level {
+    pub ratio: f32,
+    /// Color of the level, or the drawing color when `None`.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::style::hex"))]
+    pub color: Option<Color>,
+}
+
+impl Level {
+    pub fn new(ratio: f32) -> Self {
+        Self { ratio, color: None }
+    }
+}
+
+/// What is written next to each Fibonacci level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LevelLabels {
+    Hidden,
+    Ratio,
+    Price,
+    #[default]
+    Both,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fibonacci {
+    pub kind: FibonacciKind,
+    pub start: Anchor,
+    pub end: Anchor,
+    pub levels: Vec<Level>,
+    pub labels: LevelLabels,
+}
+
+impl Fibonacci {
+    /// A tool of `kind` with its default levels.
+    pub fn new(kind: FibonacciKind, start: Anchor, end: Anchor) -> Self {
+        let ratios: &[f32] = match kind {
+            FibonacciKind::Retracement => &RETRACEMENT_LEVELS,
+            FibonacciKind::Extension => &EXTENSION_LEVELS,
+            FibonacciKind::TimeZone => &TIME_ZONE_LEVELS,
+        };
+
+        Self {
+            kind,
+            start,
+            end,
+            levels: ratios.iter().copied().map(Level::new).collect(),
+            labels: LevelLabels::default(),
+        }
+    }
+
+    /// Price of every level, empty for time zones.
+    pub fn level_prices(&self) -> Vec<(Level, f32)> {
+        let range = self.end.price - self.start.price;
+
+        self.levels
+            .iter()
+            .filter_map(|level| {
+                let price = match self.kind {
+                    FibonacciKind::Retracement => self.end.price - range * level.ratio,
+                    FibonacciKind::Extension => self.start.price + range * level.ratio,
+                    FibonacciKind::TimeZone => return None,
+                };
+                Some((*level, price))
+            })
+            .collect()
+    }
+
+    /// Start time of every time zone, counted in bars of `candles`, empty
+    /// for price levels.
+    pub fn level_times(&self, candles: &[candle::Candle]) -> Vec<(Level, i64)> {
+        if self.kind != FibonacciKind::TimeZone {
+            return Vec::new();
+        }
+        let (Some(start), Some(end)) = (
+            candle::index_at(candles, self.start.time),
+            candle::index_at(candles, self.end.time),
+        ) else {
+            return Vec::new();
+        };
+
+        self.levels
+            .iter()
+            .filter_map(|level| {
+                let index = start + (end - start) * level.ratio;
+                candle::time_at(candles, index).map(|time| (*level, time))
+            })
+            .collect()
+    }
+
+    /// Text written next to `level` at `price`.
+    pub fn label(&self, level: &Level, price: Option<f32>) -> Option<String> {
+        match (self.labels, price) {
+            (LevelLabels::Hidden, _) => None,
+            (LevelLabels::Ratio, _) | (_, None) => Some(format!("{}", level.ratio)),
+            (LevelLabels::Price, Some(price)) => Some(format!("{price:.1}")),
+            (LevelLabels::Both, Some(price)) => Some(format!("{} ({price:.1})", level.ratio)),
+        }
+    }
+
+    /// Horizontal extent on screen of the price levels.
+    pub fn span(&self, projection: &Projection<'_>) -> Option<(f32, f32)> {
+        let start = self.start.position(projection)?.x;
+        let end = self.end.position(projection)?.x;
+
+        Some((start.min(end), start.max(end)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum AnnotationKind {
+    /// Text starting at the anchor.
+    Note,
+    /// An arrow pointing down at the anchor, with the text above it.
+    Arrow,
+    /// Text in a box next to the anchor, with a line pointing at it.
+    Callout,
+}
+
+/// Text pinned to a candle and price.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Annotation {
+    pub kind: AnnotationKind,
+    pub anchor: Anchor,
+    pub text: String,
+    pub font_size: f32,
+    /// Color of the annotation, or the drawing color when `None`.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::style::hex"))]
+    pub color: Option<Color>,
+}
+
+impl Annotation {
+    pub fn new(kind: AnnotationKind, anchor: Anchor, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            anchor,
+            text: text.into(),
+            font_size: 14.0,
+            color: None,
+        }
+    }
+
+    /// Approximate size of the text, as the surfaces cannot measure it.
+    pub fn text_size(&self) -> Size {
+        let characters = self.text.chars().count().max(1) as f32;
+        Size::new(characters * self.font_size * 0.6, self.font_size * 1.2)
+    }
+
+    /// Box around the text on screen.
+    pub fn text_bounds(&self, projection: &Projection<'_>) -> Option<Rectangle> {
+        let anchor = self.anchor.position(projection)?;
+        let size = self.text_size();
+
+        let top_left = match self.kind {
+            AnnotationKind::Note => anchor,
+            AnnotationKind::Arrow => Point::new(
+                anchor.x - size.width / 2.0,
+                anchor.y - ARROW_LENGTH - size.height - 2.0 * PADDING,
+            ),
+            AnnotationKind::Callout => {
+                anchor + CALLOUT_OFFSET - Vector::new(0.0, size.height + 2.0 * PADDING)
+            }
+        };
+
+        Some(Rectangle::new(top_left, size).expand(PADDING))
+    }
+
+    /// Start of the arrow pointing at the anchor, if any.
+    pub fn tail(&self, projection: &Projection<'_>) -> Option<Point> {
+        let anchor = self.anchor.position(projection)?;
+
+        match self.kind {
+            AnnotationKind::Note => None,
+            AnnotationKind::Arrow => Some(anchor - Vector::new(0.0, ARROW_LENGTH)),
+            AnnotationKind::Callout => Some(anchor + CALLOUT_OFFSET),
+        }
+    }
+}
+
+/// Two corners of a box in data coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    pub start: Anchor,
+    pub end: Anchor,
+}
+
+impl Range {
+    pub fn new(start: Anchor, end: Anchor) -> Self {
+        Self { start, end }
+    }
+
+    /// The box on screen.
+    pub fn rectangle(&self, projection: &Projection<'_>) -> Option<Rectangle> {
+        let start = self.start.position(projection)?;
+        let end = self.end.position(projection)?;
+
+        Some(Rectangle::new(
+            Point::new(start.x.min(end.x), start.y.min(end.y)),
+            Size::new((end.x - start.x).abs(), (end.y - start.y).abs()),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum Drawing {
+    TrendLine(TrendLine),
+    /// A price level across the chart, labeled on the price scale.
+    HorizontalLine(Anchor),
+    /// A point in time across the chart, labeled on the time axis.
+    VerticalLine(Anchor),
+    /// A box between two corners, labeled with the change in price.
+    PriceRange(Range),
+    /// A band between two times, labeled with the number of bars.
+    DateRange(Range),
+    Fibonacci(Fibonacci),
+    /// Text, arrows and callouts, drawn above the other drawings.
+    Annotation(Annotation),
+}
+
+/// Part of a drawing under the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handle {
+    /// One of the [`Drawing::anchors`], by index.
+    Anchor(usize),
+    /// Anywhere else on the drawing, moving it as a whole.
+    Body,
+}
+
+impl Drawing {
+    /// Points of the drawing that can be dragged on their own.
+    pub fn anchors(&self) -> Vec<Anchor> {
+        match self {
+            Self::TrendLine(line) => vec![line.start, line.end],
+            Self::HorizontalLine(anchor) | Self::VerticalLine(anchor) => vec![*anchor],
+            Self::PriceRange(range) | Self::DateRange(range) => vec![range.start, range.end],
+            Self::Fibonacci(fibonacci) => vec![fibonacci.start, fibonacci.end],
+            Self::Annotation(annotation) => vec![annotation.anchor],
+        }
+    }
+
+    fn anchors_mut(&mut self) -> Vec<&mut Anchor> {
+        match self {
+            Self::TrendLine(line) => vec![&mut line.start, &mut line.end],
+            Self::HorizontalLine(anchor) | Self::VerticalLine(anchor) => vec![anchor],
+            Self::PriceRange(range) | Self::DateRange(range) => {
+                vec![&mut range.start, &mut range.end]
+            }
+            Self::Fibonacci(fibonacci) => vec![&mut fibonacci.start, &mut fibonacci.end],
+            Self::Annotation(annotation) => vec![&mut annotation.anchor],
+        }
+    }
+
+    fn text_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Self::Annotation(annotation) => Some(&mut annotation.text),
+            _ => None,
+        }
+    }
+
+    /// Whether the anchors snap to candle prices even without the magnet.
+    pub fn snaps(&self) -> bool {
+        matches!(self, Self::Fibonacci(_))
+    }
+
+    /// Prices labeled on the price scale, with their color when it differs
+    /// from the drawing color.
+    pub fn price_labels(&self) -> Vec<(f32, Option<Color>)> {
+        match self {
+            Self::HorizontalLine(anchor) => vec![(anchor.price, None)],
+            Self::Fibonacci(fibonacci) => fibonacci
+                .level_prices()
+                .into_iter()
+                .map(|(level, price)| (price, level.color))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Times labeled on the time axis.
+    pub fn time_labels(&self) -> Vec<i64> {
+        match self {
+            Self::VerticalLine(anchor) => vec![anchor.time],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Part of the drawing within reach of `point`, preferring anchors.
+    pub fn hit(&self, projection: &Projection<'_>, point: Point) -> Option<Handle> {
+        let anchor = self.anchors().iter().position(|anchor| {
+            anchor
+                .position(projection)
+                .is_some_and(|position| position.distance(point) <= HIT_DISTANCE)
+        });
+        if let Some(index) = anchor {
+            return Some(Handle::Anchor(index));
+        }
+
+        let on_body = match self {
+            Self::TrendLine(line) => line
+                .segment(projection)
+                .is_some_and(|(from, to)| distance_to_segment(point, from, to) <= HIT_DISTANCE),
+            Self::HorizontalLine(anchor) => anchor
+                .position(projection)
+                .is_some_and(|position| (position.y - point.y).abs() <= HIT_DISTANCE),
+            Self::VerticalLine(anchor) => anchor
+                .position(projection)
+                .is_some_and(|position| (position.x - point.x).abs() <= HIT_DISTANCE),
+            Self::PriceRange(range) => range
+                .rectangle(projection)
+                .is_some_and(|rectangle| rectangle.expand(HIT_DISTANCE).contains(point)),
+            Self::DateRange(range) => range.rectangle(projection).is_some_and(|rectangle| {
+                (rectangle.x - HIT_DISTANCE..=rectangle.x + rectangle.width + HIT_DISTANCE)
+                    .contains(&point.x)
+            }),
+            Self::Fibonacci(fibonacci) => {
+                let near_price = fibonacci.span(projection).is_some_and(|(left, right)| {
+                    (left..=right).contains(&point.x)
+                        && fibonacci.level_prices().iter().any(|(_, price)| {
+                            let y = projection
+                                .viewport
+                                .transform(0.0, *price, &projection.bounds)
+                                .y;
+                            (y - point.y).abs() <= HIT_DISTANCE
+                        })
+                });
+                let near_time =
+                    fibonacci
+                        .level_times(projection.candles)
+                        .iter()
+                        .any(|(_, time)| {
+                            projection.to_screen(*time, 0.0).is_some_and(|position| {
+                                (position.x - point.x).abs() <= HIT_DISTANCE
+                            })
+                        });
+
+                near_price || near_time
+            }
+            Self::Annotation(annotation) => {
+                let on_text = annotation
+                    .text_bounds(projection)
+                    .is_some_and(|bounds| bounds.contains(point));
+                let on_arrow = annotation
+                    .tail(projection)
+                    .zip(annotation.anchor.position(projection))
+                    .is_some_and(|(tail, anchor)| {
+                        distance_to_segment(point, tail, anchor) <= HIT_DISTANCE
+                    });
+
+                on_text || on_arrow
+            }
+        };
+
+        on_body.then_some(Handle::Body)
+    }
+}
+
+fn distance_to_segment(point: Point, from: Point, to: Point) -> f32 {
+    let segment = to - from;
+    let length = segment.x * segment.x + segment.y * segment.y;
+    if length == 0.0 {
+        return point.distance(from);
+    }
+
+    let relative = point - from;
+    let t = ((relative.x * segment.x + relative.y * segment.y) / length).clamp(0.0, 1.0);
+
+    point.distance(from + segment * t)
+}
+
+/// What a press on the chart does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tool {
+    /// Selects and moves existing drawings, or pans the chart.
+    #[default]
+    Select,
+    TrendLine,
+    HorizontalLine,
+    VerticalLine,
+    PriceRange,
+    DateRange,
+    FibRetracement,
+    FibExtension,
+    FibTimeZone,
+    Note,
+    Arrow,
+    Callout,
+    /// Drags a box to zoom into, as Shift-dragging does with any tool.
+    Zoom,
+}
+
+impl Tool {
+    /// Whether the drawings of this tool are picked on candles, snapping
+    /// their anchors even without the magnet.
+    pub fn snaps(self) -> bool {
+        matches!(
+            self,
+            Self::FibRetracement | Self::FibExtension | Self::FibTimeZone
+        )
+    }
+
+    /// A new drawing with every anchor at `anchor`, or `None` for the tools
+    /// that do not draw.
+    pub fn create(self, anchor: Anchor) -> Option<Drawing> {
+        match self {
+            Self::Select | Self::Zoom => None,
+            Self::TrendLine => Some(Drawing::TrendLine(TrendLine::new(anchor, anchor))),
+            Self::HorizontalLine => Some(Drawing::HorizontalLine(anchor)),
+            Self::VerticalLine => Some(Drawing::VerticalLine(anchor)),
+            Self::PriceRange => Some(Drawing::PriceRange(Range::new(anchor, anchor))),
+            Self::DateRange => Some(Drawing::DateRange(Range::new(anchor, anchor))),
+            Self::FibRetracement => Some(Drawing::Fibonacci(Fibonacci::new(
+                FibonacciKind::Retracement,
+                anchor,
+                anchor,
+            ))),
+            Self::FibExtension => Some(Drawing::Fibonacci(Fibonacci::new(
+                FibonacciKind::Extension,
+                anchor,
+                anchor,
+            ))),
+            Self::FibTimeZone => Some(Drawing::Fibonacci(Fibonacci::new(
+                FibonacciKind::TimeZone,
+                anchor,
+                anchor,
+            ))),
+            Self::Note => Some(annotation(AnnotationKind::Note, anchor)),
+            Self::Arrow => Some(annotation(AnnotationKind::Arrow, anchor)),
+            Self::Callout => Some(annotation(AnnotationKind::Callout, anchor)),
+        }
+    }
+}
+
+fn annotation(kind: AnnotationKind, anchor: Anchor) -> Drawing {
+    Drawing::Annotation(Annotation::new(kind, anchor, "Text"))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Adds a drawing and selects it.
+    Add(Drawing),
+    Replace(usize, Drawing),
+    Remove(usize),
+    Select(Option<usize>),
+}
+
+/// The drawings of a chart, owned by the application and edited through
+/// the [`Action`]s produced by the chart.
+///
+/// Each drawing keeps an [`Id`] for as long as it exists, so that alerts
+/// can follow it while others are added and removed.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "Vec<Drawing>", into = "Vec<Drawing>")
+)]
+pub struct Drawings {
+    drawings: Vec<Drawing>,
+    ids: Vec<Id>,
+    selected: Option<usize>,
+}
+
+impl PartialEq for Drawings {
+    fn eq(&self, other: &Self) -> bool {
+        self.drawings == other.drawings && self.selected == other.selected
+    }
+}
+
+impl Drawings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Drawing> {
+        self.drawings.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Drawing> {
+        self.drawings.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.drawings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.drawings.is_empty()
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn id(&self, index: usize) -> Option<Id> {
+        self.ids.get(index).copied()
+    }
+
+    /// Index of the drawing with `id`, if it still exists.
+    pub fn position(&self, id: Id) -> Option<usize> {
+        self.ids.iter().position(|other| *other == id)
+    }
+
+    /// Inserts `drawing` with `id` at `index`, keeping the same drawing
+    /// selected.
+    pub(crate) fn insert(&mut self, index: usize, drawing: Drawing, id: Id) {
+        let index = index.min(self.drawings.len());
+        self.drawings.insert(index, drawing);
+        self.ids.insert(index, id);
+
+        if let Some(selected) = &mut self.selected
+            && *selected >= index
+        {
+            *selected += 1;
+        }
+    }
+
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            Action::Add(drawing) => {
+                self.drawings.push(drawing);
+                self.ids.push(Id::unique());
+                self.selected = Some(self.drawings.len() - 1);
+            }
+            Action::Replace(index, drawing) => {
+                if let Some(slot) = self.drawings.get_mut(index) {
+                    *slot = drawing;
+                }
+            }
+            Action::Remove(index) => {
+                if index < self.drawings.len() {
+                    self.drawings.remove(index);
+                    self.ids.remove(index);
+                    self.selected = match self.selected {
+                        Some(selected) if selected == index => None,
+                        Some(selected) if selected > index => Some(selected - 1),
+                        selected => selected,
+                    };
+                }
+            }
+            Action::Select(selected) => {
+                self.selected = selected.filter(|&index| index < self.drawings.len());
+            }
+        }
+    }
+}
+
+impl From<Vec<Drawing>> for Drawings {
+    fn from(drawings: Vec<Drawing>) -> Self {
+        Self {
+            ids: drawings.iter().map(|_| Id::unique()).collect(),
+            drawings,
+            selected: None,
+        }
+    }
+}
+
+impl From<Drawings> for Vec<Drawing> {
+    fn from(drawings: Drawings) -> Self {
+        drawings.drawings
+    }
+}
+
+/// A drawing being created or dragged.
+#[derive(Debug, Clone)]
+struct Editing {
+    /// Index of the drawing being moved, or `None` while creating one.
+    index: Option<usize>,
+    handle: Handle,
+    origin: Point,
+    original: Drawing,
+    current: Drawing,
+    /// Whether dragged anchors snap to the candle prices.
+    magnet: bool,
+}
+
+impl Editing {
+    fn drag(&mut self, position: Point, projection: &Projection<'_>) {
+        let mut current = self.original.clone();
+
+        match self.handle {
+            Handle::Anchor(index) => {
+                if let (Some(anchor), Some(new)) = (
+                    current.anchors_mut().into_iter().nth(index),
+                    place(projection, position, self.magnet),
+                ) {
+                    *anchor = new;
+                }
+            }
+            Handle::Body => {
+                let delta = position - self.origin;
+                for anchor in current.anchors_mut() {
+                    if let Some(moved) = anchor
+                        .position(projection)
+                        .and_then(|point| Anchor::at(projection, point + delta))
+                    {
+                        *anchor = moved;
+                    }
+                }
+            }
+        }
+
+        self.current = current;
+    }
+
+    fn finish(self, position: Point) -> Option<Action> {
+        match self.index {
+            None => {
+                let placed =
+                    self.current.anchors().len() == 1 || self.origin.distance(position) >= MIN_DRAG;
+                placed.then_some(Action::Add(self.current))
+            }
+            Some(index) => {
+                (self.current != self.original).then_some(Action::Replace(index, self.current))
+            }
+        }
+    }
+}
+
+/// An annotation whose text is being typed in place.
+#[derive(Debug, Clone)]
+struct Typing {
+    index: usize,
+    drawing: Drawing,
+    /// Whether the text is still the initial one, replaced by the first key.
+    fresh: bool,
+}
+
+impl Typing {
+    fn on_key(&mut self, key: &keyboard::Key, text: Option<&str>) {
+        let fresh = std::mem::take(&mut self.fresh);
+        let Some(buffer) = self.drawing.text_mut() else {
+            return;
+        };
+
+        match key {
+            keyboard::Key::Named(keyboard::key::Named::Backspace) if fresh => buffer.clear(),
+            keyboard::Key::Named(keyboard::key::Named::Backspace) => {
+                let _ = buffer.pop();
+            }
+            _ => {
+                if let Some(text) = text.filter(|text| !text.chars().any(char::is_control)) {
+                    if fresh {
+                        buffer.clear();
+                    }
+                    buffer.push_str(text);
+                } else {
+                    self.fresh = fresh;
+                }
+            }
+        }
+    }
+}
+
+/// Interaction state of the drawing layer, kept in the widget state.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Editor {
+    editing: Option<Editing>,
+    typing: Option<Typing>,
+    last_click: Option<Click>,
+}
+
+impl Editor {
+    pub(crate) fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// The drawing being edited and the index it replaces, if any.
+    pub(crate) fn preview(&self) -> Option<(Option<usize>, &Drawing)> {
+        match (&self.editing, &self.typing) {
+            (Some(editing), _) => Some((editing.index, &editing.current)),
+            (None, Some(typing)) => Some((Some(typing.index), &typing.drawing)),
+            (None, None) => None,
+        }
+    }
+
+    /// Ends typing, replacing the annotation with its new text unless it is
+    /// unchanged.
+    fn commit(&mut self, drawings: &Drawings) -> Option<Action> {
+        let typing = self.typing.take()?;
+        (drawings.get(typing.index) != Some(&typing.drawing))
+            .then_some(Action::Replace(typing.index, typing.drawing))
+    }
+
+    /// Handles `event`, returning the resulting action and whether the event
+    /// was consumed by the drawing layer.
+    pub(crate) fn on_event(
+        &mut self,
+        event: &canvas::Event,
+        projection: &Projection<'_>,
+        cursor: Cursor,
+        drawings: &Drawings,
+        tool: Tool,
+        magnet: bool,
+    ) -> (Option<Action>, bool) {
+        let bounds = projection.bounds;
+
+        if let Some(typing) = &mut self.typing {
+            match event {
+                canvas::Event::Keyboard(keyboard::Event::KeyPressed { key, text, .. }) => {
+                    match key {
+                        keyboard::Key::Named(keyboard::key::Named::Enter) => {
+                            return (self.commit(drawings), true);
+                        }
+                        keyboard::Key::Named(keyboard::key::Named::Escape) => {
+                            self.typing = None;
+                        }
+                        _ => typing.on_key(key, text.as_deref()),
+                    }
+                    return (None, true);
+                }
+                canvas::Event::Mouse(mouse::Event::ButtonPressed(_)) => {
+                    return (self.commit(drawings), true);
+                }
+                _ => {}
+            }
+        }
+
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                match cursor.position_in(bounds) {
+                    Some(position) => self.press(position, projection, drawings, tool, magnet),
+                    None => (None, false),
+                }
+            }
+            canvas::Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                match (&mut self.editing, cursor.position_from(bounds.position())) {
+                    (Some(editing), Some(position)) => {
+                        editing.drag(position, projection);
+                        (None, true)
+                    }
+                    _ => (None, false),
+                }
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                match self.editing.take() {
+                    Some(editing) => {
+                        let position = cursor
+                            .position_from(bounds.position())
+                            .unwrap_or(editing.origin);
+                        let action = editing.finish(position);
+
+                        if let Some(Action::Add(drawing @ Drawing::Annotation(_))) = &action {
+                            self.typing = Some(Typing {
+                                index: drawings.len(),
+                                drawing: drawing.clone(),
+                                fresh: true,
+                            });
+                        }
+                        (action, true)
+                    }
+                    None => (None, false),
+                }
+            }
+            canvas::Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(named),
+                ..
+            }) if cursor.is_over(bounds) => match named {
+                keyboard::key::Named::Escape if self.editing.is_some() => {
+                    self.editing = None;
+                    (None, true)
+                }
+                keyboard::key::Named::Escape => (
+                    drawings.selected().map(|_| Action::Select(None)),
+                    drawings.selected().is_some(),
+                ),
+                keyboard::key::Named::Delete | keyboard::key::Named::Backspace
+                    if self.editing.is_none() =>
+                {
+                    (
+                        drawings.selected().map(Action::Remove),
+                        drawings.selected().is_some(),
+                    )
+                }
+                _ => (None, false),
+            },
+            _ => (None, false),
+        }
+    }
+
+    fn press(
+        &mut self,
+        position: Point,
+        projection: &Projection<'_>,
+        drawings: &Drawings,
+        tool: Tool,
+        magnet: bool,
+    ) -> (Option<Action>, bool) {
+        if tool != Tool::Select {
+            let magnet = magnet || tool.snaps();
+            let Some(drawing) =
+                place(projection, position, magnet).and_then(|anchor| tool.create(anchor))
+            else {
+                return (None, false);
+            };
+
+            self.editing = Some(Editing {
+                index: None,
+                handle: Handle::Anchor(drawing.anchors().len() - 1),
+                origin: position,
+                original: drawing.clone(),
+                current: drawing,
+                magnet,
+            });
+            return (None, true);
+        }
+
+        let click = Click::new(position, mouse::Button::Left, self.last_click);
+        self.last_click = Some(click);
+
+        let hit = drawings
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, drawing)| {
+                drawing
+                    .hit(projection, position)
+                    .map(|handle| (index, handle, drawing))
+            });
+
+        match hit {
+            Some((index, _, drawing @ Drawing::Annotation(_)))
+                if click.kind() == click::Kind::Double =>
+            {
+                self.typing = Some(Typing {
+                    index,
+                    drawing: drawing.clone(),
+                    fresh: false,
+                });
+                (None, true)
+            }
+            Some((index, handle, drawing)) => {
+                self.editing = Some(Editing {
+                    index: Some(index),
+                    handle,
+                    origin: position,
+                    original: drawing.clone(),
+                    current: drawing.clone(),
+                    magnet: magnet || drawing.snaps(),
+                });
+
+                let select =
+                    (drawings.selected() != Some(index)).then_some(Action::Select(Some(index)));
+                (select, true)
+            }
+            None => (drawings.selected().map(|_| Action::Select(None)), false),
+        }
+    }
+}
+
+/// Anchor under `point`, snapped to the nearest candle price with `magnet`.
+fn place(projection: &Projection<'_>, point: Point, magnet: bool) -> Option<Anchor> {
+    if magnet {
+        Anchor::snapped(projection, point)
+    } else {
+        Anchor::at(projection, point)
+    }
+}
@@ -0,0 +1,265 @@
+//! Live candle updates delivered through an iced [`Subscription`].
+
+use std::{
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    time::Duration,
+};
+
+use futures::{StreamExt, stream::BoxStream};
+use iced::Subscription;
+
+use crate::{
+    aggregate::{Aggregator, Bars, Trade, Update},
+    candle::Candle,
+    loader::csv::CsvLoader,
+    time::Interval,
+};
+
+pub trait LiveFeed {
+    /// Streams the latest state of the newest candle every time it changes.
+    /// A candle with a new open time starts the next bar.
+    fn updates(&self) -> BoxStream<'static, anyhow::Result<Candle>>;
+}
+
+/// Runs `feed` for as long as the subscription is kept alive.
+pub fn subscription<F>(feed: F) -> Subscription<Result<Candle, String>>
+where
+    F: LiveFeed + Hash + 'static,
+{
+    Subscription::run_with(feed, updates::<F>)
+}
+
+fn updates<F: LiveFeed>(feed: &F) -> BoxStream<'static, Result<Candle, String>> {
+    feed.updates()
+        .map(|update| update.map_err(|error| error.to_string()))
+        .boxed()
+}
+
+/// Applies a feed update to a newest-first series, replacing the candle with
+/// the same open time or adding a new newest candle.
+pub fn apply(candles: &mut Vec<Candle>, update: Candle) {
+    let index = candles.partition_point(|candle| candle.time > update.time);
+
+    match candles.get_mut(index) {
+        Some(candle) if candle.time == update.time => *candle = update,
+        _ => candles.insert(index, update),
+    }
+}
+
+/// Replays trades recorded in a CSV file, aggregated into bars.
+#[derive(Debug, Clone)]
+pub struct ReplayFeed {
+    path: PathBuf,
+    loader: CsvLoader,
+    bars: Bars,
+    speed: f32,
+}
+
+impl ReplayFeed {
+    pub fn new(path: impl Into<PathBuf>, bars: Bars) -> Self {
+        Self {
+            path: path.into(),
+            loader: CsvLoader::new(),
+            bars,
+            speed: 1.0,
+        }
+    }
+
+    #[must_use]
+    pub fn loader(mut self, loader: CsvLoader) -> Self {
+        self.loader = loader;
+        self
+    }
+
+    /// Sets how many times faster than recorded the trades are replayed.
+    /// [`f32::INFINITY`] replays without waiting.
+    #[must_use]
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+}
+
+impl Hash for ReplayFeed {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.path.hash(state);
+        self.speed.to_bits().hash(state);
+        self.bars.hash(state);
+    }
+}
+
+impl LiveFeed for ReplayFeed {
+    fn updates(&self) -> BoxStream<'static, anyhow::Result<Candle>> {
+        let ReplayFeed {
+            path,
+            loader,
+            bars,
+            speed,
+        } = self.clone();
+
+        let trades = async move {
+            let loaded = loader.load_trades(&path).await?;
+
+            for error in loaded.errors {
+                tracing::warn!("{}: {error}", path.display());
+            }
+
+            anyhow::Ok(loaded.trades)
+        };
+
+        futures::stream::once(trades)
+            .flat_map(move |trades| match trades {
+                Ok(trades) => replay(trades, bars, speed),
+                Err(error) => futures::stream::once(async { Err(error) }).boxed(),
+            })
+            .boxed()
+    }
+}
+
+fn replay(
+    trades: Vec<Trade>,
+    bars: Bars,
+    speed: f32,
+) -> BoxStream<'static, anyhow::Result<Candle>> {
+    let state = (trades.into_iter(), Aggregator::new(bars), None::<i64>);
+
+    futures::stream::unfold(
+        state,
+        move |(mut trades, mut aggregator, previous)| async move {
+            loop {
+                let trade = trades.next()?;
+
+                if let Some(previous) = previous {
+                    let delay = (trade.time - previous).max(0) as f32 / 1000.0 / speed;
+                    if delay.is_finite() && delay > 0.0 {
+                        tokio::time::sleep(Duration::from_secs_f32(delay)).await;
+                    }
+                }
+
+                let candle = match aggregator.push(trade) {
+                    Update::Opened => aggregator.candles()[0],
+                    Update::Updated(index) => aggregator.candles()[index],
+                    Update::Ignored => continue,
+                };
+
+                return Some((Ok(candle), (trades, aggregator, Some(trade.time))));
+            }
+        },
+    )
+    .boxed()
+}
+
+/// Simulated prices following a random walk, starting after a given candle.
+#[derive(Debug, Clone, Copy)]
+pub struct RandomWalk {
+    last: Candle,
+    interval: Interval,
+    volatility: f32,
+    tick: Duration,
+    ticks_per_bar: u32,
+}
+
+impl RandomWalk {
+    pub fn new(last: Candle, interval: Interval) -> Self {
+        Self {
+            last,
+            interval,
+            volatility: 0.02,
+            tick: Duration::from_millis(250),
+            ticks_per_bar: 20,
+        }
+    }
+
+    /// Sets the largest move of a bar's close, relative to its open.
+    #[must_use]
+    pub fn volatility(mut self, volatility: f32) -> Self {
+        self.volatility = volatility;
+        self
+    }
+
+    /// Sets the real time between two updates.
+    #[must_use]
+    pub fn tick(mut self, tick: Duration) -> Self {
+        self.tick = tick;
+        self
+    }
+
+    #[must_use]
+    pub fn ticks_per_bar(mut self, ticks_per_bar: u32) -> Self {
+        self.ticks_per_bar = ticks_per_bar.max(1);
+        self
+    }
+}
+
+impl Hash for RandomWalk {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.last.time.hash(state);
+        self.interval.hash(state);
+        self.volatility.to_bits().hash(state);
+        self.tick.hash(state);
+        self.ticks_per_bar.hash(state);
+    }
+}
+
+impl LiveFeed for RandomWalk {
+    fn updates(&self) -> BoxStream<'static, anyhow::Result<Candle>> {
+        let walk = *self;
+        let start = walk.interval.next(walk.last.time);
+        let ticks = walk.ticks_per_bar as i64;
+        let state = (
+            Aggregator::new(Bars::Time(walk.interval)),
+            start,
+            0,
+            walk.last.close,
+        );
+
+        futures::stream::unfold(
+            state,
+            move |(mut aggregator, bar, tick, price)| async move {
+                tokio::time::sleep(walk.tick).await;
+
+                let deviation = walk.volatility / (walk.ticks_per_bar as f32).sqrt();
+                let price = price + (rand::random::<f32>() - 0.5) * 2.0 * deviation * price;
+
+                // Spread the ticks over the real length of the bar, which
+                // varies for weeks and months.
+                let next = walk.interval.next(bar);
+                aggregator.push(Trade {
+                    time: bar + (next - bar) * tick / ticks,
+                    price,
+                    size: rand::random::<f32>(),
+                });
+                let candle = aggregator.candles()[0];
+
+                // Only the bar in progress is kept.
+                let state = if tick + 1 < ticks {
+                    (aggregator, bar, tick + 1, price)
+                } else {
+                    (Aggregator::new(Bars::Time(walk.interval)), next, 0, price)
+                };
+                Some((Ok(candle), state))
+            },
+        )
+        .boxed()
+    }
+}
+
+/// A whole random candle opening at the close of `previous`.
+pub fn random_candle(previous: &Candle, time: i64, volatility: f32) -> Candle {
+    let range = previous.close * volatility;
+
+    let open = previous.close;
+    let close = open + (rand::random::<f32>() - 0.5) * range * 2.0;
+    let high = open.max(close) + rand::random::<f32>() * range / 2.0;
+    let low = open.min(close) - rand::random::<f32>() * range / 2.0;
+
+    Candle {
+        time,
+        open,
+        high,
+        low,
+        close,
+        volume: 0.0,
+    }
+}
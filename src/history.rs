@@ -0,0 +1,185 @@
+//! Undo and redo of chart edits.
+
+use crate::{
+    drawing::{self, Drawing},
+    id::Id,
+    layout::ChartLayout,
+    style::StyleOverrides,
+};
+
+/// Most edits kept for undoing.
+const LIMIT: usize = 200;
+
+/// A move through the [`History`], bound to Ctrl+Z and Ctrl+Shift+Z on the
+/// chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Undo,
+    Redo,
+}
+
+/// An edit of a [`ChartLayout`] that can be applied and reverted.
+#[derive(Debug, Clone, PartialEq)]
+enum Edit {
+    AddDrawing(usize, Drawing, Id),
+    RemoveDrawing(usize, Drawing, Id),
+    ReplaceDrawing {
+        index: usize,
+        before: Drawing,
+        after: Drawing,
+    },
+    Style {
+        before: StyleOverrides,
+        after: StyleOverrides,
+    },
+}
+
+impl Edit {
+    fn apply(&self, layout: &mut ChartLayout) {
+        match self {
+            Self::AddDrawing(index, drawing, id) => {
+                layout.drawings.insert(*index, drawing.clone(), *id);
+            }
+            Self::RemoveDrawing(index, ..) => {
+                layout.drawings.perform(drawing::Action::Remove(*index));
+            }
+            Self::ReplaceDrawing { index, after, .. } => {
+                layout
+                    .drawings
+                    .perform(drawing::Action::Replace(*index, after.clone()));
+            }
+            Self::Style { after, .. } => layout.style = *after,
+        }
+    }
+
+    fn revert(&self, layout: &mut ChartLayout) {
+        match self {
+            Self::AddDrawing(index, drawing, id) => {
+                Self::RemoveDrawing(*index, drawing.clone(), *id).apply(layout);
+            }
+            Self::RemoveDrawing(index, drawing, id) => {
+                Self::AddDrawing(*index, drawing.clone(), *id).apply(layout);
+            }
+            Self::ReplaceDrawing {
+                index,
+                before,
+                after,
+            } => Self::ReplaceDrawing {
+                index: *index,
+                before: after.clone(),
+                after: before.clone(),
+            }
+            .apply(layout),
+            Self::Style { before, .. } => layout.style = *before,
+        }
+    }
+}
+
+/// Undo and redo stacks of the edits made to a [`ChartLayout`].
+///
+/// Edits go through the history instead of straight to the layout, so that
+/// every change of the drawings and style can be reverted. The chart has no
+/// indicators yet, so these are all the edits there are to record.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies a drawing action to `layout`, recording it unless it only
+    /// changes the selection.
+    pub fn perform(&mut self, layout: &mut ChartLayout, action: drawing::Action) {
+        // The id of an added drawing is only known once it is added.
+        if let drawing::Action::Add(drawing) = &action {
+            let drawing = drawing.clone();
+            layout.drawings.perform(action);
+
+            let index = layout.drawings.len() - 1;
+            if let Some(id) = layout.drawings.id(index) {
+                self.record(Edit::AddDrawing(index, drawing, id));
+            }
+            return;
+        }
+
+        let drawings = &layout.drawings;
+        let edit = match &action {
+            drawing::Action::Add(_) | drawing::Action::Select(_) => None,
+            drawing::Action::Replace(index, after) => {
+                drawings.get(*index).map(|before| Edit::ReplaceDrawing {
+                    index: *index,
+                    before: before.clone(),
+                    after: after.clone(),
+                })
+            }
+            drawing::Action::Remove(index) => drawings
+                .get(*index)
+                .zip(drawings.id(*index))
+                .map(|(drawing, id)| Edit::RemoveDrawing(*index, drawing.clone(), id)),
+        };
+
+        layout.drawings.perform(action);
+        if let Some(edit) = edit {
+            self.record(edit);
+        }
+    }
+
+    /// Replaces the style overrides of `layout`.
+    pub fn set_style(&mut self, layout: &mut ChartLayout, style: StyleOverrides) {
+        if layout.style != style {
+            self.record(Edit::Style {
+                before: layout.style,
+                after: style,
+            });
+            layout.style = style;
+        }
+    }
+
+    /// Reverts or reapplies the last edit, returning whether there was one.
+    pub fn step(&mut self, layout: &mut ChartLayout, step: Step) -> bool {
+        match step {
+            Step::Undo => self.undo(layout),
+            Step::Redo => self.redo(layout),
+        }
+    }
+
+    pub fn undo(&mut self, layout: &mut ChartLayout) -> bool {
+        let Some(edit) = self.undo.pop() else {
+            return false;
+        };
+
+        edit.revert(layout);
+        self.redo.push(edit);
+        true
+    }
+
+    pub fn redo(&mut self, layout: &mut ChartLayout) -> bool {
+        let Some(edit) = self.redo.pop() else {
+            return false;
+        };
+
+        edit.apply(layout);
+        self.undo.push(edit);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    fn record(&mut self, edit: Edit) {
+        if self.undo.len() == LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+}
@@ -0,0 +1,97 @@
+//! What lies under a point of the chart.
+
+use iced::Point;
+
+use crate::{
+    candle,
+    drawing::Drawings,
+    marker::{self, Marker},
+    order::{self, OrderLine},
+    viewport::{Axis, Projection},
+};
+
+/// Distance in pixels within which a candle wick counts as hit.
+const CANDLE_DISTANCE: f32 = 4.0;
+
+/// Element of the chart under a point, topmost first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    PriceScale,
+    TimeScale,
+    /// A marker, by index.
+    Marker(usize),
+    /// An order line, by index.
+    Order(usize),
+    /// A drawing, by index.
+    Drawing(usize),
+    Candle,
+    /// Empty space between the candles.
+    Background,
+}
+
+/// A point of the chart and what lies under it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CandleHit {
+    /// Position relative to the chart bounds.
+    pub position: Point,
+    /// Index of the nearest candle, newest first, if any is that close.
+    pub index: Option<usize>,
+    /// Time under the point, extrapolated past the loaded candles.
+    pub time: Option<i64>,
+    pub price: f32,
+    pub target: Target,
+}
+
+impl CandleHit {
+    /// Finds what lies under `position` among the candles of `projection`
+    /// and the elements drawn over them.
+    pub fn at(
+        projection: &Projection<'_>,
+        position: Point,
+        drawings: Option<&Drawings>,
+        markers: &[Marker],
+        orders: &[OrderLine],
+    ) -> Self {
+        let bounds = &projection.bounds;
+        let viewport = projection.viewport;
+        let fraction = viewport.index_at(position.x, bounds, projection.spacing);
+        let index = (fraction.round() >= 0.0)
+            .then_some(fraction.round() as usize)
+            .filter(|index| *index < projection.candles.len());
+        let price = viewport.untransform(position.x, position.y, bounds).y;
+
+        let target = if let Some(axis) = Axis::at(bounds.size(), position) {
+            match axis {
+                Axis::Price => Target::PriceScale,
+                Axis::Time => Target::TimeScale,
+            }
+        } else if let Some(marker) = marker::hit(&marker::place(markers, projection), position) {
+            Target::Marker(marker)
+        } else if let Some(order) = order::line_at(orders, projection, position) {
+            Target::Order(order)
+        } else if let Some(drawing) = drawings.and_then(|drawings| {
+            drawings
+                .iter()
+                .rposition(|drawing| drawing.hit(projection, position).is_some())
+        }) {
+            Target::Drawing(drawing)
+        } else if index.is_some_and(|index| {
+            let candle = &projection.candles[index];
+            let high = viewport.transform(0.0, candle.high, bounds).y;
+            let low = viewport.transform(0.0, candle.low, bounds).y;
+            (high - CANDLE_DISTANCE..=low + CANDLE_DISTANCE).contains(&position.y)
+        }) {
+            Target::Candle
+        } else {
+            Target::Background
+        };
+
+        Self {
+            position,
+            index,
+            time: candle::time_at(projection.candles, fraction),
+            price,
+            target,
+        }
+    }
+}
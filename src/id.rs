@@ -0,0 +1,23 @@
+//! Identifiers that stay with drawings and alerts while others are added,
+//! moved or removed.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// An identifier unique within the running application. Identifiers are
+/// handed out afresh when drawings and alerts are loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Id(u64);
+
+impl Id {
+    pub fn unique() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for Id {
+    fn default() -> Self {
+        Self::unique()
+    }
+}
@@ -0,0 +1,42 @@
+//! Saving and restoring the state of a chart.
+
+use crate::{drawing::Drawings, style::StyleOverrides, time::Interval, viewport::Viewport};
+
+/// The parts of a chart worth keeping across restarts: its drawings,
+/// interval, viewport and style overrides. The crate has no indicators,
+/// panes or chart types yet, so there are none to keep.
+///
+/// The application owns the layout and passes it to
+/// [`CandleChart::layout`](crate::widget::CandleChart::layout). Kept current
+/// through the `on_drawing` and `on_viewport_change` messages of the chart,
+/// or replaced by the one carried by its `on_layout_change` message, it can
+/// be exported at any time.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ChartLayout {
+    pub interval: Option<Interval>,
+    pub viewport: Option<Viewport>,
+    pub style: StyleOverrides,
+    pub drawings: Drawings,
+}
+
+impl ChartLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ChartLayout {
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(input: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(input)
+    }
+}
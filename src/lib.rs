@@ -1,5 +1,24 @@
+pub mod aggregate;
+pub mod alert;
+pub mod bindings;
 pub mod candle;
+pub mod drawing;
+pub mod feed;
+pub mod history;
+pub mod hit;
+pub mod id;
+pub mod layout;
+pub mod loader;
+pub mod marker;
+pub mod menu;
+pub mod order;
 pub mod renderer;
+pub mod replay;
+pub mod resample;
+pub mod source;
 pub mod style;
+pub mod surface;
+pub mod svg;
+pub mod time;
 pub mod viewport;
 pub mod widget;
@@ -0,0 +1,132 @@
+//! Parsers turning external OHLCV data into [`Candle`] series.
+//!
+//! Loaders return candles in the chart's newest-first order together with
+//! the rows that could not be parsed, so a few bad lines don't discard a file.
+
+pub mod csv;
+pub mod json;
+
+use std::fmt;
+
+use crate::{aggregate::Trade, candle::Candle, time};
+
+/// How timestamps are encoded in the source data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Numbers above `10^11` are read as milliseconds, smaller ones as
+    /// seconds and anything else as ISO-8601.
+    #[default]
+    Auto,
+    UnixSeconds,
+    UnixMillis,
+    Iso8601,
+}
+
+impl TimestampFormat {
+    /// Parses `value` into unix milliseconds.
+    pub fn parse(self, value: &str) -> Option<i64> {
+        let value = value.trim();
+
+        match self {
+            Self::Auto => match value.parse::<f64>() {
+                Ok(number) if number.abs() >= 1e11 => Some(number as i64),
+                Ok(number) => Some((number * 1000.0) as i64),
+                Err(_) => time::parse_iso8601(value),
+            },
+            Self::UnixSeconds => value
+                .parse::<f64>()
+                .ok()
+                .map(|seconds| (seconds * 1000.0) as i64),
+            Self::UnixMillis => value.parse::<f64>().ok().map(|millis| millis as i64),
+            Self::Iso8601 => time::parse_iso8601(value),
+        }
+    }
+}
+
+/// A row that was skipped while loading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    /// One-based line or element number in the source.
+    pub line: usize,
+    pub kind: RowErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowErrorKind {
+    MissingField(&'static str),
+    InvalidNumber {
+        field: &'static str,
+        value: String,
+    },
+    InvalidTimestamp(String),
+    /// High below low, or open/close outside of the high-low range.
+    InconsistentPrices,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.kind {
+            RowErrorKind::MissingField(field) => write!(f, "missing {field}"),
+            RowErrorKind::InvalidNumber { field, value } => {
+                write!(f, "invalid {field} value {value:?}")
+            }
+            RowErrorKind::InvalidTimestamp(value) => write!(f, "invalid timestamp {value:?}"),
+            RowErrorKind::InconsistentPrices => write!(f, "prices outside of the high-low range"),
+        }
+    }
+}
+
+impl std::error::Error for RowError {}
+
+/// Candles parsed from a source, newest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Loaded {
+    pub candles: Vec<Candle>,
+    pub errors: Vec<RowError>,
+}
+
+impl Loaded {
+    pub(crate) fn push(&mut self, line: usize, candle: Result<Candle, RowErrorKind>) {
+        match candle {
+            Ok(candle) => self.candles.push(candle),
+            Err(kind) => self.errors.push(RowError { line, kind }),
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> Self {
+        self.candles
+            .sort_by_key(|candle| std::cmp::Reverse(candle.time));
+        self
+    }
+}
+
+/// Trades parsed from a source, oldest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadedTrades {
+    pub trades: Vec<Trade>,
+    pub errors: Vec<RowError>,
+}
+
+pub(crate) fn parse_price(field: &'static str, value: &str) -> Result<f32, RowErrorKind> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| RowErrorKind::InvalidNumber {
+            field,
+            value: value.to_owned(),
+        })
+}
+
+pub(crate) fn validate(candle: Candle) -> Result<Candle, RowErrorKind> {
+    let consistent = candle.low <= candle.high
+        && (candle.low..=candle.high).contains(&candle.open)
+        && (candle.low..=candle.high).contains(&candle.close);
+
+    if consistent {
+        Ok(candle)
+    } else {
+        Err(RowErrorKind::InconsistentPrices)
+    }
+}
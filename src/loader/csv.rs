@@ -0,0 +1,394 @@
+use std::{fmt, io, path::Path};
+
+use super::{Loaded, LoadedTrades, RowError, RowErrorKind, TimestampFormat, parse_price, validate};
+use crate::{aggregate::Trade, candle::Candle};
+
+const TIME_ALIASES: [&str; 6] = [
+    "time",
+    "timestamp",
+    "date",
+    "datetime",
+    "open_time",
+    "opentime",
+];
+
+const SIZE_ALIASES: [&str; 5] = ["size", "qty", "quantity", "amount", "volume"];
+
+/// Selects a field of a record either by position or by header name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_owned())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Columns {
+    pub time: Column,
+    pub open: Column,
+    pub high: Column,
+    pub low: Column,
+    pub close: Column,
+    pub volume: Option<Column>,
+}
+
+impl Columns {
+    /// `time, open, high, low, close, volume` in that order.
+    pub fn positional() -> Self {
+        Self {
+            time: Column::Index(0),
+            open: Column::Index(1),
+            high: Column::Index(2),
+            low: Column::Index(3),
+            close: Column::Index(4),
+            volume: Some(Column::Index(5)),
+        }
+    }
+
+    fn from_header(header: &[String]) -> Result<Self, Error> {
+        let find = |name: &str| {
+            header
+                .iter()
+                .position(|field| field.eq_ignore_ascii_case(name))
+                .map(Column::Index)
+        };
+
+        let time = TIME_ALIASES
+            .iter()
+            .find_map(|alias| find(alias))
+            .ok_or_else(|| Error::UnknownColumn("time".to_owned()))?;
+        let require = |name: &str| find(name).ok_or_else(|| Error::UnknownColumn(name.to_owned()));
+
+        Ok(Self {
+            time,
+            open: require("open")?,
+            high: require("high")?,
+            low: require("low")?,
+            close: require("close")?,
+            volume: find("volume").or_else(|| find("vol")),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Header {
+    /// Treat the first record as a header when it contains a field that is
+    /// neither a number nor a timestamp.
+    #[default]
+    Auto,
+    Present,
+    Absent,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    UnknownColumn(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "failed to read file: {error}"),
+            Error::UnknownColumn(name) => write!(f, "column {name:?} not found in header"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Loads delimited OHLCV text such as CSV or TSV.
+#[derive(Debug, Clone, Default)]
+pub struct CsvLoader {
+    delimiter: Option<char>,
+    header: Header,
+    columns: Option<Columns>,
+    timestamp: TimestampFormat,
+}
+
+impl CsvLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tab separated values.
+    pub fn tsv() -> Self {
+        Self::new().delimiter('\t')
+    }
+
+    /// Sets the field delimiter. Without one it is guessed from the first line.
+    #[must_use]
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    #[must_use]
+    pub fn header(mut self, header: Header) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the column mapping. Without one, columns are looked up by name in
+    /// the header, or taken in [`Columns::positional`] order when there is none.
+    #[must_use]
+    pub fn columns(mut self, columns: Columns) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    #[must_use]
+    pub fn timestamp(mut self, timestamp: TimestampFormat) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub async fn load(&self, path: impl AsRef<Path>) -> Result<Loaded, Error> {
+        let input = tokio::fs::read_to_string(path).await?;
+        self.parse(&input)
+    }
+
+    pub async fn load_trades(&self, path: impl AsRef<Path>) -> Result<LoadedTrades, Error> {
+        let input = tokio::fs::read_to_string(path).await?;
+        self.parse_trades(&input)
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Loaded, Error> {
+        let Some(records) = self.records(input) else {
+            return Ok(Loaded::default());
+        };
+
+        let columns = match (&self.columns, &records.header) {
+            (Some(columns), _) => columns.clone(),
+            (None, Some(header)) => Columns::from_header(header)?,
+            (None, None) => Columns::positional(),
+        };
+
+        let indices = Indices {
+            time: records.resolve(&columns.time)?,
+            open: records.resolve(&columns.open)?,
+            high: records.resolve(&columns.high)?,
+            low: records.resolve(&columns.low)?,
+            close: records.resolve(&columns.close)?,
+            volume: columns
+                .volume
+                .as_ref()
+                .map(|column| records.resolve(column))
+                .transpose()?,
+        };
+
+        let mut loaded = Loaded::default();
+
+        for (line, record) in records.lines {
+            let record = split_record(record, records.delimiter);
+            loaded.push(line, self.parse_record(&record, &indices));
+        }
+
+        Ok(loaded.finish())
+    }
+
+    /// Parses recorded trades as `time, price, size` columns, looked up by
+    /// name when there is a header. The column mapping only applies to candles.
+    pub fn parse_trades(&self, input: &str) -> Result<LoadedTrades, Error> {
+        let Some(records) = self.records(input) else {
+            return Ok(LoadedTrades::default());
+        };
+
+        let (time, price, size) = match &records.header {
+            Some(header) => {
+                let find = |names: &[&str], column: &str| {
+                    names
+                        .iter()
+                        .find_map(|name| {
+                            header
+                                .iter()
+                                .position(|field| field.eq_ignore_ascii_case(name))
+                        })
+                        .ok_or_else(|| Error::UnknownColumn(column.to_owned()))
+                };
+
+                (
+                    find(&TIME_ALIASES, "time")?,
+                    find(&["price"], "price")?,
+                    find(&SIZE_ALIASES, "size")?,
+                )
+            }
+            None => (0, 1, 2),
+        };
+
+        let mut loaded = LoadedTrades::default();
+
+        for (line, record) in records.lines {
+            let record = split_record(record, records.delimiter);
+            let field = |name: &'static str, index: usize| {
+                record
+                    .get(index)
+                    .map(String::as_str)
+                    .ok_or(RowErrorKind::MissingField(name))
+            };
+
+            let trade = field("time", time).and_then(|value| {
+                Ok(Trade {
+                    time: self
+                        .timestamp
+                        .parse(value)
+                        .ok_or_else(|| RowErrorKind::InvalidTimestamp(value.to_owned()))?,
+                    price: parse_price("price", field("price", price)?)?,
+                    size: parse_price("size", field("size", size)?)?,
+                })
+            });
+
+            match trade {
+                Ok(trade) => loaded.trades.push(trade),
+                Err(kind) => loaded.errors.push(RowError { line, kind }),
+            }
+        }
+
+        loaded.trades.sort_by_key(|trade| trade.time);
+        Ok(loaded)
+    }
+
+    fn records<'i>(&self, input: &'i str) -> Option<Records<'i>> {
+        let mut lines: Vec<_> = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        let &(_, first) = lines.first()?;
+
+        let delimiter = self.delimiter.unwrap_or_else(|| detect_delimiter(first));
+        let first = split_record(first, delimiter);
+
+        let has_header = match self.header {
+            Header::Auto => first.iter().any(|field| {
+                field.parse::<f64>().is_err() && TimestampFormat::Iso8601.parse(field).is_none()
+            }),
+            Header::Present => true,
+            Header::Absent => false,
+        };
+
+        let header = if has_header {
+            lines.remove(0);
+            Some(first)
+        } else {
+            None
+        };
+
+        Some(Records {
+            header,
+            delimiter,
+            lines,
+        })
+    }
+
+    fn parse_record(&self, record: &[String], indices: &Indices) -> Result<Candle, RowErrorKind> {
+        let field = |name: &'static str, index: usize| {
+            record
+                .get(index)
+                .map(String::as_str)
+                .ok_or(RowErrorKind::MissingField(name))
+        };
+
+        let time = field("time", indices.time)?;
+        let time = self
+            .timestamp
+            .parse(time)
+            .ok_or_else(|| RowErrorKind::InvalidTimestamp(time.to_owned()))?;
+
+        let volume = match indices.volume.and_then(|index| record.get(index)) {
+            Some(volume) if !volume.is_empty() => parse_price("volume", volume)?,
+            _ => 0.0,
+        };
+
+        validate(Candle {
+            time,
+            open: parse_price("open", field("open", indices.open)?)?,
+            high: parse_price("high", field("high", indices.high)?)?,
+            low: parse_price("low", field("low", indices.low)?)?,
+            close: parse_price("close", field("close", indices.close)?)?,
+            volume,
+        })
+    }
+}
+
+struct Records<'i> {
+    header: Option<Vec<String>>,
+    delimiter: char,
+    lines: Vec<(usize, &'i str)>,
+}
+
+impl Records<'_> {
+    fn resolve(&self, column: &Column) -> Result<usize, Error> {
+        match (column, &self.header) {
+            (Column::Index(index), _) => Ok(*index),
+            (Column::Name(name), Some(header)) => header
+                .iter()
+                .position(|field| field.eq_ignore_ascii_case(name))
+                .ok_or_else(|| Error::UnknownColumn(name.clone())),
+            (Column::Name(name), None) => Err(Error::UnknownColumn(name.clone())),
+        }
+    }
+}
+
+struct Indices {
+    time: usize,
+    open: usize,
+    high: usize,
+    low: usize,
+    close: usize,
+    volume: Option<usize>,
+}
+
+fn detect_delimiter(line: &str) -> char {
+    if line.contains('\t') {
+        '\t'
+    } else if line.matches(';').count() > line.matches(',').count() {
+        ';'
+    } else {
+        ','
+    }
+}
+
+fn split_record(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => {
+                fields.push(field.trim().to_owned());
+                field.clear();
+            }
+            c => field.push(c),
+        }
+    }
+
+    fields.push(field.trim().to_owned());
+    fields
+}
@@ -0,0 +1,181 @@
+use std::{fmt, io, path::Path};
+
+use serde_json::Value;
+
+use super::{Loaded, RowErrorKind, TimestampFormat, parse_price, validate};
+use crate::candle::Candle;
+
+/// Fields that commonly wrap the kline list in exchange responses.
+const ENVELOPES: [&str; 5] = ["data", "result", "list", "klines", "candles"];
+
+const TIME_KEYS: [&str; 6] = ["t", "time", "timestamp", "open_time", "openTime", "start"];
+const OPEN_KEYS: [&str; 2] = ["o", "open"];
+const HIGH_KEYS: [&str; 2] = ["h", "high"];
+const LOW_KEYS: [&str; 2] = ["l", "low"];
+const CLOSE_KEYS: [&str; 2] = ["c", "close"];
+const VOLUME_KEYS: [&str; 3] = ["v", "volume", "vol"];
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// The document holds no array of klines.
+    UnexpectedLayout,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "failed to read file: {error}"),
+            Error::Json(error) => write!(f, "invalid JSON: {error}"),
+            Error::UnexpectedLayout => write!(f, "no kline array found in document"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+/// Loads exchange-style klines.
+///
+/// Accepts arrays of `[open_time, open, high, low, close, volume, ...]` and
+/// arrays of objects keyed `t/o/h/l/c/v` or by full names, either at the top
+/// level or wrapped in a `data`, `result`, `list`, `klines` or `candles` field.
+/// Numbers may be encoded as strings.
+#[derive(Debug, Clone, Default)]
+pub struct JsonLoader {
+    timestamp: TimestampFormat,
+}
+
+impl JsonLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn timestamp(mut self, timestamp: TimestampFormat) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub async fn load(&self, path: impl AsRef<Path>) -> Result<Loaded, Error> {
+        let input = tokio::fs::read_to_string(path).await?;
+        self.parse(&input)
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Loaded, Error> {
+        let document: Value = serde_json::from_str(input)?;
+        self.parse_value(&document)
+    }
+
+    pub fn parse_value(&self, document: &Value) -> Result<Loaded, Error> {
+        let klines = find_klines(document).ok_or(Error::UnexpectedLayout)?;
+        let mut loaded = Loaded::default();
+
+        for (i, kline) in klines.iter().enumerate() {
+            let candle = match kline {
+                Value::Array(fields) => self.parse_array(fields),
+                Value::Object(_) => self.parse_object(kline),
+                _ => Err(RowErrorKind::MissingField("time")),
+            };
+            loaded.push(i + 1, candle);
+        }
+
+        Ok(loaded.finish())
+    }
+
+    fn parse_array(&self, fields: &[Value]) -> Result<Candle, RowErrorKind> {
+        let field = |name: &'static str, index: usize| {
+            fields.get(index).ok_or(RowErrorKind::MissingField(name))
+        };
+
+        let volume = match fields.get(5) {
+            Some(volume) => number("volume", volume)?,
+            None => 0.0,
+        };
+
+        validate(Candle {
+            time: self.time(field("time", 0)?)?,
+            open: number("open", field("open", 1)?)?,
+            high: number("high", field("high", 2)?)?,
+            low: number("low", field("low", 3)?)?,
+            close: number("close", field("close", 4)?)?,
+            volume,
+        })
+    }
+
+    fn parse_object(&self, object: &Value) -> Result<Candle, RowErrorKind> {
+        let field = |name: &'static str, keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| object.get(key))
+                .ok_or(RowErrorKind::MissingField(name))
+        };
+
+        let volume = match field("volume", &VOLUME_KEYS) {
+            Ok(volume) => number("volume", volume)?,
+            Err(_) => 0.0,
+        };
+
+        validate(Candle {
+            time: self.time(field("time", &TIME_KEYS)?)?,
+            open: number("open", field("open", &OPEN_KEYS)?)?,
+            high: number("high", field("high", &HIGH_KEYS)?)?,
+            low: number("low", field("low", &LOW_KEYS)?)?,
+            close: number("close", field("close", &CLOSE_KEYS)?)?,
+            volume,
+        })
+    }
+
+    fn time(&self, value: &Value) -> Result<i64, RowErrorKind> {
+        let text = match value {
+            Value::String(text) => text.clone(),
+            Value::Number(number) => number.to_string(),
+            _ => value.to_string(),
+        };
+
+        self.timestamp
+            .parse(&text)
+            .ok_or(RowErrorKind::InvalidTimestamp(text))
+    }
+}
+
+fn find_klines(value: &Value) -> Option<&Vec<Value>> {
+    match value {
+        Value::Array(klines) => Some(klines),
+        Value::Object(object) => ENVELOPES
+            .iter()
+            .filter_map(|key| object.get(*key))
+            .find_map(find_klines),
+        _ => None,
+    }
+}
+
+fn number(field: &'static str, value: &Value) -> Result<f32, RowErrorKind> {
+    match value {
+        Value::Number(number) => {
+            number
+                .as_f64()
+                .map(|number| number as f32)
+                .ok_or_else(|| RowErrorKind::InvalidNumber {
+                    field,
+                    value: number.to_string(),
+                })
+        }
+        Value::String(text) => parse_price(field, text),
+        _ => Err(RowErrorKind::InvalidNumber {
+            field,
+            value: value.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,168 @@
+//! Trade markers pinned to candles.
+
+use std::collections::HashMap;
+
+use iced::{Color, Point};
+
+use crate::{candle::Candle, time, viewport::Projection};
+
+/// Width and height of a marker in pixels.
+pub const SIZE: f32 = 10.0;
+/// Space in pixels between a candle and its markers, and between stacked
+/// markers.
+const GAP: f32 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// Drawn below the candle.
+    Buy,
+    /// Drawn above the candle.
+    Sell,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Shape {
+    /// A triangle pointing at the candle.
+    #[default]
+    Arrow,
+    Circle,
+    Flag,
+}
+
+/// A fill or order shown at the candle it happened in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub time: i64,
+    pub price: f32,
+    pub side: Side,
+    pub shape: Shape,
+    /// Label drawn next to the marker.
+    pub text: Option<String>,
+    /// Color of the marker, or the bullish or bearish color when `None`.
+    pub color: Option<Color>,
+}
+
+impl Marker {
+    pub fn new(time: i64, price: f32, side: Side) -> Self {
+        Self {
+            time,
+            price,
+            side,
+            shape: Shape::default(),
+            text: None,
+            color: None,
+        }
+    }
+
+    pub fn buy(time: i64, price: f32) -> Self {
+        Self::new(time, price, Side::Buy)
+    }
+
+    pub fn sell(time: i64, price: f32) -> Self {
+        Self::new(time, price, Side::Sell)
+    }
+
+    #[must_use]
+    pub fn shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    #[must_use]
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    #[must_use]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Lines of the tooltip shown while hovering the marker.
+    pub fn tooltip(&self) -> Vec<String> {
+        let side = match self.side {
+            Side::Buy => "Buy",
+            Side::Sell => "Sell",
+        };
+
+        let mut lines = vec![
+            format!("{side} @ {:.2}", self.price),
+            time::format(self.time),
+        ];
+        lines.extend(self.text.clone());
+        lines
+    }
+}
+
+/// Index of the candle whose period contains `time` in a newest-first
+/// series, if any does. The newest candle is taken to last as long as the
+/// one before it.
+pub fn bar(candles: &[Candle], time: i64) -> Option<usize> {
+    let index = candles.partition_point(|candle| candle.time > time);
+    let candle = candles.get(index)?;
+
+    if index == 0 {
+        let span = candles.get(1).map_or(1, |older| candle.time - older.time);
+        return (time < candle.time + span).then_some(0);
+    }
+    Some(index)
+}
+
+/// Where a marker is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// Index of the marker in the placed slice.
+    pub marker: usize,
+    pub center: Point,
+}
+
+/// Places buy markers below the low of their candle and sell markers above
+/// its high, stacking the markers that share a bar and a side away from it.
+pub fn place(markers: &[Marker], projection: &Projection<'_>) -> Vec<Placement> {
+    let mut stacks: HashMap<(usize, Side), usize> = HashMap::new();
+
+    markers
+        .iter()
+        .enumerate()
+        .filter_map(|(index, marker)| {
+            let bar = bar(projection.candles, marker.time)?;
+            let candle = &projection.candles[bar];
+
+            let slot = stacks.entry((bar, marker.side)).or_default();
+            let offset = GAP + SIZE / 2.0 + *slot as f32 * (SIZE + GAP);
+            *slot += 1;
+
+            let x = bar as f32 * projection.spacing;
+            let center = match marker.side {
+                Side::Buy => {
+                    let low = projection
+                        .viewport
+                        .transform(x, candle.low, &projection.bounds);
+                    Point::new(low.x, low.y + offset)
+                }
+                Side::Sell => {
+                    let high = projection
+                        .viewport
+                        .transform(x, candle.high, &projection.bounds);
+                    Point::new(high.x, high.y - offset)
+                }
+            };
+
+            Some(Placement {
+                marker: index,
+                center,
+            })
+        })
+        .collect()
+}
+
+/// Index of the marker under `point`, the last placed one first.
+pub fn hit(placements: &[Placement], point: Point) -> Option<usize> {
+    placements
+        .iter()
+        .rev()
+        .find(|placement| placement.center.distance(point) <= SIZE / 2.0 + 1.0)
+        .map(|placement| placement.marker)
+}
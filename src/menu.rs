@@ -0,0 +1,282 @@
+//! Context menu opened by right-clicking the chart.
+
+use iced::{
+    Event, Point, Rectangle, Renderer, Size, Vector,
+    advanced::{
+        Clipboard, Layout, Renderer as _, Shell, clipboard, graphics::geometry::Renderer as _,
+        layout::Node, overlay, renderer,
+    },
+    keyboard, mouse,
+    widget::canvas::Frame,
+};
+
+use crate::{
+    alert::{Alert, Condition, Level},
+    candle::Candle,
+    drawing::{self, Anchor, Drawing},
+    hit::CandleHit,
+    layout::ChartLayout,
+    renderer::CandleRenderer,
+    style::{Catalog, StyleOverrides},
+    viewport::ViewportManager,
+    widget::State,
+};
+
+/// Height in pixels of a menu item.
+pub const ROW_HEIGHT: f32 = 24.0;
+/// Smallest width in pixels of the menu.
+const MIN_WIDTH: f32 = 140.0;
+
+/// Builds the menu items for the hit the menu opens at.
+pub type ItemsFn<'a, Message> = Box<dyn Fn(&CandleHit) -> Vec<Item<Message>> + 'a>;
+
+/// What choosing a menu item does.
+#[derive(Debug, Clone)]
+pub enum Command<Message> {
+    /// Produces the message.
+    Message(Message),
+    /// Returns the viewport to the newest candle.
+    ResetView,
+    /// Copies the price under the cursor to the clipboard.
+    CopyPrice,
+    /// Adds a horizontal line at the price under the cursor, through the
+    /// chart's drawing message.
+    HorizontalLine,
+    /// Adds an alert on the close crossing the price under the cursor,
+    /// through the chart's alert adding message.
+    Alert,
+}
+
+#[derive(Debug, Clone)]
+pub struct Item<Message> {
+    pub label: String,
+    pub command: Command<Message>,
+}
+
+impl<Message> Item<Message> {
+    /// An item producing `message`.
+    pub fn new(label: impl Into<String>, message: Message) -> Self {
+        Self::command(label, Command::Message(message))
+    }
+
+    pub fn command(label: impl Into<String>, command: Command<Message>) -> Self {
+        Self {
+            label: label.into(),
+            command,
+        }
+    }
+}
+
+/// The items every chart offers: reset view, copy price, add a horizontal
+/// line and add an alert.
+///
+/// There are no toggle log scale or remove indicator items: the price scale
+/// is linear only and the crate has no indicators. Applications with their
+/// own can add them as [`Item::new`] messages.
+pub fn standard<Message>() -> Vec<Item<Message>> {
+    vec![
+        Item::command("Reset view", Command::ResetView),
+        Item::command("Copy price", Command::CopyPrice),
+        Item::command("Add horizontal line", Command::HorizontalLine),
+        Item::command("Add alert here", Command::Alert),
+    ]
+}
+
+/// Size of a menu listing `labels`.
+pub fn size<'b>(labels: impl IntoIterator<Item = &'b str>) -> Size {
+    let (rows, chars) = labels.into_iter().fold((0, 0), |(rows, chars), label| {
+        (rows + 1, chars.max(label.chars().count()))
+    });
+
+    Size::new(
+        (chars as f32 * 7.0 + 16.0).max(MIN_WIDTH),
+        rows as f32 * ROW_HEIGHT,
+    )
+}
+
+/// The open menu, drawn over the chart at the hit that opened it.
+pub(crate) struct Menu<'b, Message, Theme>
+where
+    Theme: Catalog,
+{
+    pub(crate) items: Vec<Item<Message>>,
+    pub(crate) hit: CandleHit,
+    /// Chart bounds in window coordinates.
+    pub(crate) bounds: Rectangle,
+    pub(crate) candles: &'b [Candle],
+    pub(crate) state: &'b mut State,
+    pub(crate) class: &'b Theme::Class<'static>,
+    pub(crate) overrides: StyleOverrides,
+    pub(crate) on_drawing: Option<&'b dyn Fn(drawing::Action) -> Message>,
+    pub(crate) on_viewport_change: Option<&'b dyn Fn(ViewportManager, Size) -> Message>,
+    pub(crate) on_alert_add: Option<&'b dyn Fn(Alert) -> Message>,
+    /// Layout of the chart and the message reporting its changes.
+    pub(crate) layout: Option<(ChartLayout, &'b dyn Fn(ChartLayout) -> Message)>,
+}
+
+impl<Message, Theme> Menu<'_, Message, Theme>
+where
+    Message: Clone,
+    Theme: Catalog,
+{
+    /// Index of the item under `cursor`.
+    fn hovered(&self, layout: Layout<'_>, cursor: mouse::Cursor) -> Option<usize> {
+        let position = cursor.position_in(layout.bounds())?;
+        let row = (position.y / ROW_HEIGHT) as usize;
+        (row < self.items.len()).then_some(row)
+    }
+
+    fn choose(
+        &mut self,
+        index: usize,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        let hit = self.hit;
+
+        match &self.items[index].command {
+            Command::Message(message) => shell.publish(message.clone()),
+            Command::ResetView => {
+                let candles = self.candles;
+                self.state
+                    .viewport
+                    .animate(|viewport| viewport.reset(candles));
+                self.state.invalidate();
+                if let Some(on_viewport_change) = self.on_viewport_change {
+                    shell.publish(on_viewport_change(
+                        self.state.viewport.clone(),
+                        self.bounds.size(),
+                    ));
+                }
+                if let Some((layout, on_layout_change)) = &self.layout {
+                    let mut layout = layout.clone();
+                    layout.viewport = Some(self.state.viewport.target());
+                    shell.publish(on_layout_change(layout));
+                }
+            }
+            Command::CopyPrice => {
+                clipboard.write(clipboard::Kind::Standard, format!("{:.1}", hit.price));
+            }
+            Command::HorizontalLine => {
+                if let (Some(on_drawing), Some(time)) = (self.on_drawing, hit.time) {
+                    let action =
+                        drawing::Action::Add(Drawing::HorizontalLine(Anchor::new(time, hit.price)));
+                    if let Some((layout, on_layout_change)) = &self.layout {
+                        let mut layout = layout.clone();
+                        layout.drawings.perform(action.clone());
+                        shell.publish(on_layout_change(layout));
+                    }
+                    shell.publish(on_drawing(action));
+                }
+            }
+            Command::Alert => {
+                if let Some(on_alert_add) = self.on_alert_add {
+                    let alert = Alert::new(Level::Price(hit.price), Condition::Cross);
+                    shell.publish(on_alert_add(alert));
+                }
+            }
+        }
+    }
+}
+
+impl<Message, Theme> overlay::Overlay<Message, Theme, Renderer> for Menu<'_, Message, Theme>
+where
+    Message: Clone,
+    Theme: Catalog,
+{
+    fn layout(&mut self, _renderer: &Renderer, bounds: Size) -> Node {
+        let size = size(self.items.iter().map(|item| item.label.as_str()));
+        let cursor = self.bounds.position() + Vector::new(self.hit.position.x, self.hit.position.y);
+
+        // Open towards the inside of the window near its edges.
+        let x = if cursor.x + size.width > bounds.width {
+            cursor.x - size.width
+        } else {
+            cursor.x
+        };
+        let y = if cursor.y + size.height > bounds.height {
+            cursor.y - size.height
+        } else {
+            cursor.y
+        };
+
+        Node::new(size).move_to(Point::new(x.max(0.0), y.max(0.0)))
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        let bounds = layout.bounds();
+        let style = self.overrides.apply(theme.style(self.class));
+        let labels: Vec<_> = self.items.iter().map(|item| item.label.as_str()).collect();
+
+        let mut frame = Frame::new(renderer, bounds.size());
+        CandleRenderer::draw_context_menu(
+            &mut frame,
+            &style,
+            &labels,
+            self.hovered(layout, cursor),
+        );
+
+        renderer.with_translation(bounds.position() - Point::ORIGIN, |renderer| {
+            renderer.draw_geometry(frame.into_geometry());
+        });
+    }
+
+    fn update(
+        &mut self,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                shell.request_redraw();
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(button)) => {
+                if *button == mouse::Button::Left
+                    && let Some(index) = self.hovered(layout, cursor)
+                {
+                    self.choose(index, clipboard, shell);
+                    shell.capture_event();
+                } else if cursor.is_over(layout.bounds()) {
+                    shell.capture_event();
+                    return;
+                }
+                // Choosing an item or pressing elsewhere closes the menu.
+                self.state.menu = None;
+                shell.request_redraw();
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(keyboard::key::Named::Escape),
+                ..
+            }) => {
+                self.state.menu = None;
+                shell.capture_event();
+                shell.request_redraw();
+            }
+            _ => {}
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if self.hovered(layout, cursor).is_some() {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
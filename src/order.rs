@@ -0,0 +1,201 @@
+//! Order and position lines on the price pane.
+
+use iced::{
+    Color, Event, Point,
+    mouse::{self, Cursor},
+};
+
+use crate::{style::Style, viewport::Projection};
+
+/// Distance in pixels within which a line can be grabbed.
+const GRAB_DISTANCE: f32 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderKind {
+    Limit,
+    Stop,
+    TakeProfit,
+    /// An open position, labeled with its profit or loss at the last close.
+    Position,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderLine {
+    pub kind: OrderKind,
+    pub price: f32,
+    /// Signed size of the order or position, negative for sells and shorts.
+    pub quantity: f32,
+    /// Text shown before the price in the label.
+    pub label: Option<String>,
+    /// Whether the line can be dragged to a new price.
+    pub draggable: bool,
+    /// Color of the line, or a color picked from its kind when `None`.
+    pub color: Option<Color>,
+}
+
+impl OrderLine {
+    pub fn new(kind: OrderKind, price: f32, quantity: f32) -> Self {
+        Self {
+            kind,
+            price,
+            quantity,
+            label: None,
+            // Positions are moved by trading, not by dragging.
+            draggable: kind != OrderKind::Position,
+            color: None,
+        }
+    }
+
+    pub fn limit(price: f32, quantity: f32) -> Self {
+        Self::new(OrderKind::Limit, price, quantity)
+    }
+
+    pub fn stop(price: f32, quantity: f32) -> Self {
+        Self::new(OrderKind::Stop, price, quantity)
+    }
+
+    pub fn take_profit(price: f32, quantity: f32) -> Self {
+        Self::new(OrderKind::TakeProfit, price, quantity)
+    }
+
+    pub fn position(price: f32, quantity: f32) -> Self {
+        Self::new(OrderKind::Position, price, quantity)
+    }
+
+    #[must_use]
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    #[must_use]
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
+
+    #[must_use]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Profit or loss of a position at `price`, `None` for orders.
+    pub fn pnl(&self, price: f32) -> Option<f32> {
+        (self.kind == OrderKind::Position).then_some((price - self.price) * self.quantity)
+    }
+
+    /// Text of the label box, given the last close for positions.
+    pub fn text(&self, last: Option<f32>) -> String {
+        let name = self.label.clone().unwrap_or_else(|| {
+            match self.kind {
+                OrderKind::Limit => "Limit",
+                OrderKind::Stop => "Stop",
+                OrderKind::TakeProfit => "TP",
+                OrderKind::Position => "Position",
+            }
+            .to_owned()
+        });
+
+        let mut text = format!("{name} {} @ {:.1}", self.quantity, self.price);
+        if let Some(pnl) = last.and_then(|last| self.pnl(last)) {
+            text.push_str(&format!("  P&L {pnl:+.2}"));
+        }
+        text
+    }
+
+    /// Color of the line, given the last close for positions.
+    pub fn resolve_color(&self, style: &Style, last: Option<f32>) -> Color {
+        self.color.unwrap_or(match self.kind {
+            OrderKind::Limit => style.drawing,
+            OrderKind::Stop => style.bearish,
+            OrderKind::TakeProfit => style.bullish,
+            OrderKind::Position => match last.and_then(|last| self.pnl(last)) {
+                Some(pnl) if pnl < 0.0 => style.bearish,
+                _ => style.bullish,
+            },
+        })
+    }
+}
+
+/// Index of the line under `point`, the last one first.
+pub fn line_at(lines: &[OrderLine], projection: &Projection<'_>, point: Point) -> Option<usize> {
+    lines
+        .iter()
+        .rposition(|line| is_near(line, projection, point))
+}
+
+/// Index of the draggable line under `point`, the last one first.
+pub fn hit(lines: &[OrderLine], projection: &Projection<'_>, point: Point) -> Option<usize> {
+    lines
+        .iter()
+        .rposition(|line| line.draggable && is_near(line, projection, point))
+}
+
+fn is_near(line: &OrderLine, projection: &Projection<'_>, point: Point) -> bool {
+    let y = projection
+        .viewport
+        .transform(0.0, line.price, &projection.bounds)
+        .y;
+    (y - point.y).abs() <= GRAB_DISTANCE
+}
+
+/// Drags order lines to a new price.
+#[derive(Debug, Default)]
+pub(crate) struct Dragging {
+    /// Index of the dragged line and its price under the cursor.
+    current: Option<(usize, f32)>,
+}
+
+impl Dragging {
+    pub(crate) fn is_dragging(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Index and price of the line being dragged.
+    pub(crate) fn preview(&self) -> Option<(usize, f32)> {
+        self.current
+    }
+
+    /// Handles `event`, returning the index and new price of a line dropped
+    /// at a different price, and whether the event was captured.
+    pub(crate) fn on_event(
+        &mut self,
+        event: &Event,
+        projection: &Projection<'_>,
+        cursor: Cursor,
+        lines: &[OrderLine],
+    ) -> (Option<(usize, f32)>, bool) {
+        let bounds = &projection.bounds;
+        let price_at = |y: f32| projection.viewport.untransform(0.0, y, bounds).y;
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let Some(point) = cursor.position_in(*bounds) else {
+                    return (None, false);
+                };
+                let Some(index) = hit(lines, projection, point) else {
+                    return (None, false);
+                };
+                self.current = Some((index, lines[index].price));
+                (None, true)
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let (Some((index, _)), Some(point)) = (self.current, cursor.position_in(*bounds))
+                else {
+                    return (None, self.current.is_some());
+                };
+                self.current = Some((index, price_at(point.y)));
+                (None, true)
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let Some((index, price)) = self.current.take() else {
+                    return (None, false);
+                };
+                let moved = lines.get(index).is_some_and(|line| line.price != price);
+                (moved.then_some((index, price)), true)
+            }
+            _ => (None, false),
+        }
+    }
+}
@@ -1,10 +1,18 @@
-use iced::{
-    Color, Point, Rectangle, Size,
-    mouse::Cursor,
-    widget::canvas::{self, LineDash, Path, Stroke},
-};
+use iced::{Color, Point, Rectangle, Size, Vector, mouse::Cursor};
 
-use crate::{candle::Candle, style::Style, viewport::ViewportManager};
+use crate::{
+    alert::{Alert, Monitor},
+    candle::{self, Candle},
+    drawing::{Annotation, AnnotationKind, Drawing, Drawings, Fibonacci},
+    marker::{self, Marker, Shape, Side},
+    menu,
+    order::{OrderKind, OrderLine},
+    replay::{Control, Replay},
+    style::Style,
+    surface::{Line, Surface},
+    time,
+    viewport::{Projection, ViewportManager},
+};
 
 #[inline]
 fn get_candle_color(candle: &Candle, style: &Style) -> Color {
@@ -39,10 +47,25 @@ fn find_step(span: f32) -> f32 {
     factor
 }
 
+/// A price highlighted on the price scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceMark {
+    pub price: f32,
+    pub color: Color,
+}
+
+/// Width in pixels of the price scale along the right edge.
+pub const PRICE_SCALE_WIDTH: f32 = 100.0;
+/// Height in pixels of the time labels along the bottom edge.
+pub const TIME_SCALE_HEIGHT: f32 = 22.0;
+
+/// Size in pixels of the head of annotation arrows.
+const ARROW_HEAD: f32 = 6.0;
+
 pub struct CandleRenderer;
 impl CandleRenderer {
     fn draw_candle(
-        frame: &mut canvas::Frame,
+        frame: &mut impl Surface,
         viewport: &ViewportManager,
         style: &Style,
         candle: &Candle,
@@ -52,13 +75,12 @@ impl CandleRenderer {
         let base_x = index as f32 * style.candle_spacing;
         let color = get_candle_color(candle, style);
 
-        let wick = Path::line(
+        frame.stroke_line(
             viewport.transform(base_x, candle.high, bounds),
             viewport.transform(base_x, candle.low, bounds),
+            Line::solid(2.0, color),
         );
 
-        frame.stroke(&wick, Stroke::default().with_width(2.0).with_color(color));
-
         let (top, bottom) = if candle.open < candle.close {
             (candle.close, candle.open)
         } else {
@@ -68,12 +90,11 @@ impl CandleRenderer {
         let opos = viewport.transform(base_x - style.candle_width, top, bounds);
         let cpos = viewport.transform(base_x + style.candle_width, bottom, bounds);
 
-        let body = Path::rectangle(opos, Size::new(cpos.x - opos.x, cpos.y - opos.y));
-        frame.fill(&body, color);
+        frame.fill_rectangle(opos, Size::new(cpos.x - opos.x, cpos.y - opos.y), color);
     }
 
     fn draw_price_line(
-        frame: &mut canvas::Frame,
+        frame: &mut impl Surface,
         viewport: &ViewportManager,
         style: &Style,
         candle: &Candle,
@@ -83,65 +104,47 @@ impl CandleRenderer {
         let price = candle.close;
         let color = get_candle_color(candle, style);
 
-        let stroke = Stroke {
-            width: 1.0,
-            line_dash: canvas::LineDash {
-                segments: &[1.0, 2.0],
-                offset: 0,
-            },
-            style: canvas::Style::Solid(color),
-            ..Stroke::default()
-        };
-
-        let line = Path::line(
+        frame.stroke_line(
             viewport.transform(window.x, price, bounds),
             viewport.transform(window.x + window.width, price, bounds),
+            Line::dashed(1.0, color, &[1.0, 2.0]),
         );
-
-        frame.stroke(&line, stroke);
     }
 
     fn draw_crosshair(
-        frame: &mut canvas::Frame,
+        frame: &mut impl Surface,
         viewport: &ViewportManager,
         style: &Style,
         cursor: Point,
         window: &Rectangle,
         bounds: &Rectangle,
     ) {
-        let stroke = Stroke {
-            width: 1.0,
-            line_dash: LineDash {
-                segments: &[5.0, 6.0],
-                offset: 0,
-            },
-            style: canvas::Style::Solid(style.crosshair),
-            ..Stroke::default()
-        };
+        let line = Line::dashed(1.0, style.crosshair, &[5.0, 6.0]);
 
         let mut snap_x = window.x + window.width * (cursor.x / bounds.width);
         snap_x = (snap_x / style.candle_spacing).round() * style.candle_spacing;
 
-        let v_line = Path::line(
+        frame.stroke_line(
             viewport.transform(snap_x, window.y, bounds),
             viewport.transform(snap_x, window.y + window.height, bounds),
+            line,
         );
-        frame.stroke(&v_line, stroke);
 
         let price = window.y + window.height * (1.0 - cursor.y / bounds.height);
-        let h_line = Path::line(
+        frame.stroke_line(
             viewport.transform(window.x, price, bounds),
             viewport.transform(window.x + window.width, price, bounds),
+            line,
         );
-        frame.stroke(&h_line, stroke);
     }
 
     fn draw_price_scale(
-        frame: &mut canvas::Frame,
+        frame: &mut impl Surface,
         viewport: &ViewportManager,
         style: &Style,
         window: &Rectangle,
         bounds: &Rectangle,
+        marks: &[PriceMark],
     ) {
         let step = find_step(window.height);
 
@@ -163,51 +166,716 @@ impl CandleRenderer {
             //    },
             //);
 
-            let text = canvas::Text {
-                content: format!("{:.1}", price),
-                position: Point::new(h_end.x - 100.0, h_end.y - 8.0),
-                size: 16.into(),
-                color: style.axis_color,
-                ..Default::default()
-            };
-            frame.fill_text(text);
+            frame.fill_text(
+                format!("{:.1}", price),
+                Point::new(h_end.x - PRICE_SCALE_WIDTH, h_end.y - 8.0),
+                16.0,
+                style.axis_color,
+            );
+        }
+
+        for mark in marks {
+            let y = viewport.transform(0.0, mark.price, bounds).y;
+            Self::draw_price_label(frame, style, mark.price, y, mark.color, bounds);
         }
     }
 
     pub fn draw_chart(
-        frame: &mut canvas::Frame,
+        frame: &mut impl Surface,
         viewport: &ViewportManager,
         style: &Style,
-        candles: &Vec<Candle>,
+        candles: &[Candle],
         window: &Rectangle,
         bounds: &Rectangle,
     ) {
         if candles.is_empty() {
             return;
         }
-        let rect = Path::rectangle(Point::ORIGIN, bounds.size());
 
-        frame.fill(&rect, style.background);
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), style.background);
 
         for (i, candle) in candles.iter().enumerate() {
-            Self::draw_candle(frame, viewport, style, candle, i, &bounds);
+            Self::draw_candle(frame, viewport, style, candle, i, bounds);
+        }
+
+        Self::draw_price_line(frame, viewport, style, &candles[0], window, bounds);
+    }
+
+    /// Marks the left edge of the loaded history while older candles are
+    /// being fetched.
+    pub fn draw_loading(
+        frame: &mut impl Surface,
+        viewport: &ViewportManager,
+        style: &Style,
+        candles: &[Candle],
+        window: &Rectangle,
+        bounds: &Rectangle,
+    ) {
+        let oldest = candles.len().saturating_sub(1) as f32 * style.candle_spacing;
+        let edge = viewport
+            .transform(oldest + style.candle_spacing / 2.0, window.y, bounds)
+            .x
+            .max(0.0);
+
+        frame.stroke_line(
+            Point::new(edge, 0.0),
+            Point::new(edge, bounds.height),
+            Line::dashed(1.0, style.axis_color, &[2.0, 4.0]),
+        );
+
+        frame.fill_text(
+            "Loading...".to_owned(),
+            Point::new((edge - 90.0).max(8.0), bounds.height / 2.0 - 8.0),
+            16.0,
+            style.axis_color,
+        );
+    }
+
+    /// Draws the replay control bar, or a hint while the start point is
+    /// being picked.
+    pub fn draw_replay_controls(
+        frame: &mut impl Surface,
+        style: &Style,
+        replay: &Replay,
+        bounds: &Rectangle,
+    ) {
+        if replay.is_picking() {
+            frame.fill_text(
+                "Click a candle to start the replay".to_owned(),
+                Point::new(8.0, 8.0),
+                16.0,
+                style.axis_color,
+            );
+            return;
         }
 
-        Self::draw_price_line(frame, viewport, style, &candles[0], &window, &bounds);
+        for control in Control::ALL {
+            let button = control.bounds();
+            if button.x + button.width > bounds.width {
+                break;
+            }
+
+            frame.fill_rectangle(button.position(), button.size(), style.crosshair);
+            frame.fill_text(
+                control.label(replay),
+                Point::new(button.x + 8.0, button.y + 4.0),
+                14.0,
+                style.axis_color,
+            );
+        }
     }
 
     pub fn draw_overlay(
-        frame: &mut canvas::Frame,
+        frame: &mut impl Surface,
         viewport: &ViewportManager,
         style: &Style,
         cursor: &Cursor,
         window: &Rectangle,
         bounds: &Rectangle,
+        marks: &[PriceMark],
     ) {
         if let Some(cursor_pos) = cursor.position_in(*bounds) {
-            Self::draw_crosshair(frame, viewport, style, cursor_pos, &window, &bounds);
+            Self::draw_crosshair(frame, viewport, style, cursor_pos, window, bounds);
+        }
+
+        Self::draw_price_scale(frame, viewport, style, window, bounds, marks);
+    }
+
+    /// Draws `drawings`, with `preview` standing in for the drawing being
+    /// edited, or added on top while a new one is created.
+    pub fn draw_drawings(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        drawings: &Drawings,
+        preview: Option<(Option<usize>, &Drawing)>,
+    ) {
+        for (drawing, selected) in with_preview(drawings, preview) {
+            if !matches!(drawing, Drawing::Annotation(_)) {
+                Self::draw_drawing(frame, projection, style, drawing, selected);
+            }
+        }
+    }
+
+    /// Draws the annotations among `drawings`, meant for the overlay so that
+    /// they stay above the candles and the other drawings.
+    pub fn draw_annotations(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        drawings: &Drawings,
+        preview: Option<(Option<usize>, &Drawing)>,
+    ) {
+        for (drawing, selected) in with_preview(drawings, preview) {
+            if let Drawing::Annotation(annotation) = drawing {
+                Self::draw_annotation(frame, projection, style, annotation);
+                if selected {
+                    Self::draw_handles(frame, projection, style, drawing);
+                }
+            }
+        }
+    }
+
+    /// Prices of `drawings` to highlight on the price scale.
+    pub fn price_marks(
+        style: &Style,
+        drawings: &Drawings,
+        preview: Option<(Option<usize>, &Drawing)>,
+    ) -> Vec<PriceMark> {
+        with_preview(drawings, preview)
+            .flat_map(|(drawing, _)| drawing.price_labels())
+            .map(|(price, color)| PriceMark {
+                price,
+                color: color.unwrap_or(style.drawing),
+            })
+            .collect()
+    }
+
+    /// Labels the times marked by `drawings` on the time axis.
+    pub fn draw_drawing_labels(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        drawings: &Drawings,
+        preview: Option<(Option<usize>, &Drawing)>,
+    ) {
+        let bounds = &projection.bounds;
+
+        for (drawing, _) in with_preview(drawings, preview) {
+            for time in drawing.time_labels() {
+                if let Some(position) = projection.to_screen(time, 0.0) {
+                    Self::draw_time_label(frame, style, time, position.x, style.drawing, bounds);
+                }
+            }
+        }
+    }
+
+    /// Draws `price` in a box of `color` on the price scale at height `y`.
+    fn draw_price_label(
+        frame: &mut impl Surface,
+        style: &Style,
+        price: f32,
+        y: f32,
+        color: Color,
+        bounds: &Rectangle,
+    ) {
+        frame.fill_rectangle(
+            Point::new(bounds.width - PRICE_SCALE_WIDTH, y - 10.0),
+            Size::new(PRICE_SCALE_WIDTH, 20.0),
+            color,
+        );
+        frame.fill_text(
+            format!("{:.1}", price),
+            Point::new(bounds.width - 96.0, y - 8.0),
+            14.0,
+            style.background,
+        );
+    }
+
+    /// Draws `time` in a box of `color` at the bottom of the chart.
+    fn draw_time_label(
+        frame: &mut impl Surface,
+        style: &Style,
+        time: i64,
+        x: f32,
+        color: Color,
+        bounds: &Rectangle,
+    ) {
+        frame.fill_rectangle(
+            Point::new(x - 65.0, bounds.height - TIME_SCALE_HEIGHT),
+            Size::new(130.0, 20.0),
+            color,
+        );
+        frame.fill_text(
+            time::format(time),
+            Point::new(x - 61.0, bounds.height - 20.0),
+            14.0,
+            style.background,
+        );
+    }
+
+    fn draw_drawing(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        drawing: &Drawing,
+        selected: bool,
+    ) {
+        let bounds = &projection.bounds;
+        let line = Line::solid(1.5, style.drawing);
+        let fill = Color {
+            a: 0.15,
+            ..style.drawing
+        };
+
+        match drawing {
+            Drawing::TrendLine(trend) => {
+                if let Some((from, to)) = trend.segment(projection) {
+                    frame.stroke_line(from, to, line);
+                }
+            }
+            Drawing::HorizontalLine(anchor) => {
+                if let Some(position) = anchor.position(projection) {
+                    frame.stroke_line(
+                        Point::new(0.0, position.y),
+                        Point::new(bounds.width, position.y),
+                        line,
+                    );
+                }
+            }
+            Drawing::VerticalLine(anchor) => {
+                if let Some(position) = anchor.position(projection) {
+                    frame.stroke_line(
+                        Point::new(position.x, 0.0),
+                        Point::new(position.x, bounds.height),
+                        line,
+                    );
+                }
+            }
+            Drawing::PriceRange(range) => {
+                if let Some(rectangle) = range.rectangle(projection) {
+                    frame.fill_rectangle(rectangle.position(), rectangle.size(), fill);
+                    Self::draw_outline(frame, rectangle, line);
+
+                    let change = range.end.price - range.start.price;
+                    let percent = change / range.start.price * 100.0;
+                    frame.fill_text(
+                        format!("{change:+.1} ({percent:+.2}%)"),
+                        Point::new(rectangle.center_x() - 50.0, rectangle.y - 20.0),
+                        14.0,
+                        style.drawing,
+                    );
+                }
+            }
+            Drawing::DateRange(range) => {
+                if let Some(rectangle) = range.rectangle(projection) {
+                    frame.fill_rectangle(
+                        Point::new(rectangle.x, 0.0),
+                        Size::new(rectangle.width, bounds.height),
+                        fill,
+                    );
+
+                    let bars = candle::index_at(projection.candles, range.start.time)
+                        .zip(candle::index_at(projection.candles, range.end.time))
+                        .map_or(0.0, |(start, end)| (start - end).abs().round());
+                    frame.fill_text(
+                        format!(
+                            "{bars} bars, {}",
+                            time::format_duration(range.end.time - range.start.time)
+                        ),
+                        Point::new(rectangle.center_x() - 50.0, rectangle.y - 20.0),
+                        14.0,
+                        style.drawing,
+                    );
+                }
+            }
+            Drawing::Fibonacci(fibonacci) => {
+                Self::draw_fibonacci(frame, projection, style, fibonacci);
+            }
+            Drawing::Annotation(annotation) => {
+                Self::draw_annotation(frame, projection, style, annotation);
+            }
+        }
+
+        if selected {
+            Self::draw_handles(frame, projection, style, drawing);
+        }
+    }
+
+    fn draw_handles(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        drawing: &Drawing,
+    ) {
+        for anchor in drawing.anchors() {
+            if let Some(position) = anchor.position(projection) {
+                frame.fill_rectangle(
+                    position - Vector::new(3.0, 3.0),
+                    Size::new(6.0, 6.0),
+                    style.drawing,
+                );
+            }
+        }
+    }
+
+    fn draw_annotation(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        annotation: &Annotation,
+    ) {
+        let color = annotation.color.unwrap_or(style.drawing);
+        let (Some(anchor), Some(text_bounds)) = (
+            annotation.anchor.position(projection),
+            annotation.text_bounds(projection),
+        ) else {
+            return;
+        };
+        let line = Line::solid(1.5, color);
+
+        match annotation.kind {
+            AnnotationKind::Note => {}
+            AnnotationKind::Arrow => {
+                if let Some(tail) = annotation.tail(projection) {
+                    frame.stroke_line(tail, anchor, line);
+                }
+                frame.stroke_line(anchor, anchor + Vector::new(-ARROW_HEAD, -ARROW_HEAD), line);
+                frame.stroke_line(anchor, anchor + Vector::new(ARROW_HEAD, -ARROW_HEAD), line);
+            }
+            AnnotationKind::Callout => {
+                if let Some(tail) = annotation.tail(projection) {
+                    frame.stroke_line(anchor, tail, line);
+                }
+                frame.fill_rectangle(text_bounds.position(), text_bounds.size(), style.background);
+                Self::draw_outline(frame, text_bounds, line);
+            }
+        }
+
+        let padding = (text_bounds.height - annotation.text_size().height) / 2.0;
+        frame.fill_text(
+            annotation.text.clone(),
+            text_bounds.position() + Vector::new(padding, padding),
+            annotation.font_size,
+            color,
+        );
+    }
+
+    fn draw_fibonacci(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        fibonacci: &Fibonacci,
+    ) {
+        let bounds = &projection.bounds;
+        let (Some(start), Some(end)) = (
+            fibonacci.start.position(projection),
+            fibonacci.end.position(projection),
+        ) else {
+            return;
+        };
+
+        frame.stroke_line(start, end, Line::dashed(1.0, style.drawing, &[4.0, 4.0]));
+
+        let (left, right) = (start.x.min(end.x), start.x.max(end.x));
+        for (level, price) in fibonacci.level_prices() {
+            let color = level.color.unwrap_or(style.drawing);
+            let y = projection.viewport.transform(0.0, price, bounds).y;
+
+            frame.stroke_line(
+                Point::new(left, y),
+                Point::new(right, y),
+                Line::solid(1.0, color),
+            );
+            if let Some(label) = fibonacci.label(&level, Some(price)) {
+                frame.fill_text(label, Point::new(left + 4.0, y - 16.0), 12.0, color);
+            }
+        }
+
+        for (level, time) in fibonacci.level_times(projection.candles) {
+            let color = level.color.unwrap_or(style.drawing);
+            let Some(position) = projection.to_screen(time, 0.0) else {
+                continue;
+            };
+
+            frame.stroke_line(
+                Point::new(position.x, 0.0),
+                Point::new(position.x, bounds.height),
+                Line::solid(1.0, color),
+            );
+            if let Some(label) = fibonacci.label(&level, None) {
+                frame.fill_text(label, Point::new(position.x + 4.0, 4.0), 12.0, color);
+            }
+        }
+    }
+
+    /// Draws `markers` next to their candles.
+    pub fn draw_markers(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        markers: &[Marker],
+    ) {
+        let half = marker::SIZE / 2.0;
+
+        for placement in marker::place(markers, projection) {
+            let marker = &markers[placement.marker];
+            let Point { x, y } = placement.center;
+            let color = marker.color.unwrap_or(match marker.side {
+                Side::Buy => style.bullish,
+                Side::Sell => style.bearish,
+            });
+
+            match marker.shape {
+                Shape::Arrow => {
+                    // Point at the candle: up below it, down above it.
+                    let tip = match marker.side {
+                        Side::Buy => -half,
+                        Side::Sell => half,
+                    };
+                    frame.fill_polygon(
+                        &[
+                            Point::new(x, y + tip),
+                            Point::new(x + half, y - tip),
+                            Point::new(x - half, y - tip),
+                        ],
+                        color,
+                    );
+                }
+                Shape::Circle => frame.fill_circle(placement.center, half, color),
+                Shape::Flag => {
+                    frame.stroke_line(
+                        Point::new(x - half, y - half),
+                        Point::new(x - half, y + half),
+                        Line::solid(1.5, color),
+                    );
+                    frame.fill_rectangle(
+                        Point::new(x - half, y - half),
+                        Size::new(marker::SIZE, half),
+                        color,
+                    );
+                }
+            }
+
+            if let Some(text) = &marker.text {
+                frame.fill_text(
+                    text.clone(),
+                    Point::new(x + marker::SIZE, y - 6.0),
+                    12.0,
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Draws `lines` across the chart with a label box at their left end,
+    /// `dragging` moving the line it indexes to a new price.
+    pub fn draw_order_lines(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        lines: &[OrderLine],
+        dragging: Option<(usize, f32)>,
+    ) {
+        let bounds = &projection.bounds;
+        let last = projection.candles.first().map(|candle| candle.close);
+
+        for (line, price) in with_dragging(lines, dragging) {
+            let y = projection.viewport.transform(0.0, price, bounds).y;
+            let color = line.resolve_color(style, last);
+            let stroke = match line.kind {
+                OrderKind::Position => Line::solid(1.5, color),
+                _ => Line::dashed(1.5, color, &[6.0, 4.0]),
+            };
+            // Stop short of the price scale, where the mark shows the price.
+            let end = Point::new(bounds.width - PRICE_SCALE_WIDTH, y);
+            frame.stroke_line(Point::new(0.0, y), end, stroke);
+
+            let mut label = line.clone();
+            label.price = price;
+            let text = label.text(last);
+            let size = Size::new(text.chars().count() as f32 * 7.0 + 8.0, 18.0);
+            frame.fill_rectangle(Point::new(8.0, y - 9.0), size, color);
+            frame.fill_text(text, Point::new(12.0, y - 7.0), 12.0, style.background);
+        }
+    }
+
+    /// Tags the levels of `alerts` beside the price scale, filled once
+    /// `monitor` saw them trigger on the newest candle.
+    pub fn draw_alerts(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        alerts: &[Alert],
+        drawings: Option<&Drawings>,
+        monitor: &Monitor,
+    ) {
+        let Some(newest) = projection.candles.first() else {
+            return;
+        };
+        let bounds = &projection.bounds;
+
+        for alert in alerts {
+            let Some((low, high)) =
+                alert
+                    .level
+                    .bounds_at(projection.candles, drawings, newest.time)
+            else {
+                continue;
+            };
+            let text = alert.name.clone().unwrap_or_else(|| "Alert".to_owned());
+            let size = Size::new(text.chars().count() as f32 * 7.0 + 8.0, 18.0);
+            let triggered = monitor.is_triggered(alert);
+
+            let prices = if low == high {
+                vec![low]
+            } else {
+                vec![low, high]
+            };
+            for price in prices {
+                let y = projection.viewport.transform(0.0, price, bounds).y;
+                let tag = Rectangle::new(
+                    Point::new(bounds.width - PRICE_SCALE_WIDTH - 4.0 - size.width, y - 9.0),
+                    size,
+                );
+
+                let color = if triggered {
+                    frame.fill_rectangle(tag.position(), size, style.drawing);
+                    style.background
+                } else {
+                    frame.fill_rectangle(tag.position(), size, style.background);
+                    Self::draw_outline(frame, tag, Line::solid(1.0, style.drawing));
+                    style.drawing
+                };
+                frame.fill_text(
+                    text.clone(),
+                    tag.position() + Vector::new(4.0, 2.0),
+                    12.0,
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Prices of order `lines` to highlight on the price scale.
+    pub fn order_marks(
+        style: &Style,
+        lines: &[OrderLine],
+        dragging: Option<(usize, f32)>,
+        last: Option<f32>,
+    ) -> Vec<PriceMark> {
+        with_dragging(lines, dragging)
+            .map(|(line, price)| PriceMark {
+                price,
+                color: line.resolve_color(style, last),
+            })
+            .collect()
+    }
+
+    /// Shows the tooltip of the marker under `cursor`, if any.
+    pub fn draw_marker_tooltip(
+        frame: &mut impl Surface,
+        projection: &Projection<'_>,
+        style: &Style,
+        markers: &[Marker],
+        cursor: Point,
+    ) {
+        let placements = marker::place(markers, projection);
+        let Some(index) = marker::hit(&placements, cursor) else {
+            return;
+        };
+
+        let lines = markers[index].tooltip();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as f32
+            * 7.0;
+        let size = Size::new(width + 8.0, lines.len() as f32 * 16.0 + 8.0);
+        let position = Point::new(
+            (cursor.x + 12.0).min(projection.bounds.width - size.width),
+            (cursor.y + 12.0).min(projection.bounds.height - size.height),
+        );
+        let tooltip = Rectangle::new(position, size);
+
+        frame.fill_rectangle(position, size, style.background);
+        Self::draw_outline(frame, tooltip, Line::solid(1.0, style.axis_color));
+
+        for (row, line) in lines.into_iter().enumerate() {
+            frame.fill_text(
+                line,
+                position + Vector::new(4.0, 4.0 + row as f32 * 16.0),
+                12.0,
+                style.axis_color,
+            );
+        }
+    }
+
+    /// Shades the box being dragged to zoom into.
+    pub fn draw_selection(frame: &mut impl Surface, style: &Style, selection: Rectangle) {
+        frame.fill_rectangle(
+            selection.position(),
+            selection.size(),
+            Color {
+                a: 0.15,
+                ..style.drawing
+            },
+        );
+        Self::draw_outline(frame, selection, Line::solid(1.0, style.drawing));
+    }
+
+    /// Draws a context menu listing `labels` from the origin of `frame`,
+    /// highlighting the `hovered` one.
+    pub fn draw_context_menu(
+        frame: &mut impl Surface,
+        style: &Style,
+        labels: &[&str],
+        hovered: Option<usize>,
+    ) {
+        let size = menu::size(labels.iter().copied());
+
+        frame.fill_rectangle(Point::ORIGIN, size, style.background);
+        Self::draw_outline(
+            frame,
+            Rectangle::new(Point::ORIGIN, size),
+            Line::solid(1.0, style.axis_color),
+        );
+
+        for (row, label) in labels.iter().enumerate() {
+            let top = row as f32 * menu::ROW_HEIGHT;
+            let color = if hovered == Some(row) {
+                frame.fill_rectangle(
+                    Point::new(0.0, top),
+                    Size::new(size.width, menu::ROW_HEIGHT),
+                    style.drawing,
+                );
+                style.background
+            } else {
+                style.axis_color
+            };
+            frame.fill_text((*label).to_owned(), Point::new(8.0, top + 5.0), 14.0, color);
         }
+    }
+
+    fn draw_outline(frame: &mut impl Surface, rectangle: Rectangle, line: Line<'_>) {
+        let top_left = rectangle.position();
+        let top_right = Point::new(rectangle.x + rectangle.width, rectangle.y);
+        let bottom_right = top_right + Vector::new(0.0, rectangle.height);
+        let bottom_left = top_left + Vector::new(0.0, rectangle.height);
 
-        Self::draw_price_scale(frame, viewport, style, &window, &bounds);
+        frame.stroke_line(top_left, top_right, line);
+        frame.stroke_line(top_right, bottom_right, line);
+        frame.stroke_line(bottom_right, bottom_left, line);
+        frame.stroke_line(bottom_left, top_left, line);
     }
 }
+
+/// `lines` paired with their price, `dragging` replacing the price of the
+/// line it indexes.
+fn with_dragging(
+    lines: &[OrderLine],
+    dragging: Option<(usize, f32)>,
+) -> impl Iterator<Item = (&OrderLine, f32)> {
+    lines
+        .iter()
+        .enumerate()
+        .map(move |(index, line)| match dragging {
+            Some((dragged, price)) if dragged == index => (line, price),
+            _ => (line, line.price),
+        })
+}
+
+/// `drawings` paired with whether they are selected, with `preview`
+/// replacing the drawing it edits or, for a new one, drawn last.
+fn with_preview<'b>(
+    drawings: &'b Drawings,
+    preview: Option<(Option<usize>, &'b Drawing)>,
+) -> impl Iterator<Item = (&'b Drawing, bool)> {
+    drawings
+        .iter()
+        .enumerate()
+        .filter(move |(index, _)| preview.is_none_or(|(edited, _)| edited != Some(*index)))
+        .map(|(index, drawing)| (drawing, drawings.selected() == Some(index)))
+        .chain(preview.map(|(_, drawing)| (drawing, true)))
+}
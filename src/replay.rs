@@ -0,0 +1,192 @@
+//! Bar replay: hiding the future and stepping through history.
+
+use std::time::Duration;
+
+use iced::{Point, Rectangle, Size, Subscription};
+
+use crate::candle::Candle;
+
+/// Playback speeds cycled through by [`Control::Speed`], in bars per second.
+pub const SPEEDS: [f32; 4] = [1.0, 2.0, 5.0, 10.0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Replay {
+    /// Index of the newest visible candle in the newest-first series, or
+    /// `None` while the start point is being picked on the chart.
+    pub cursor: Option<usize>,
+    pub playing: bool,
+    /// Bars revealed per second while playing.
+    pub speed: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    /// Starts the replay with the candle at the given index as the newest.
+    Start(usize),
+    Play,
+    Pause,
+    /// Reveals the next candle.
+    StepForward,
+    /// Hides the newest visible candle again.
+    StepBack,
+    Speed(f32),
+    /// Reveals the next candle while playing.
+    Tick,
+    /// Asks the application to leave replay mode.
+    Exit,
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Replay {
+    /// A replay waiting for its start point to be picked on the chart.
+    pub fn new() -> Self {
+        Self {
+            cursor: None,
+            playing: false,
+            speed: SPEEDS[0],
+        }
+    }
+
+    pub fn starting_at(cursor: usize) -> Self {
+        Self {
+            cursor: Some(cursor),
+            ..Self::new()
+        }
+    }
+
+    pub fn is_picking(&self) -> bool {
+        self.cursor.is_none()
+    }
+
+    /// Applies `action` to a replay of `len` candles. [`Action::Exit`] is
+    /// left to the application.
+    pub fn perform(&mut self, action: Action, len: usize) {
+        let oldest = len.saturating_sub(1);
+
+        match action {
+            Action::Start(cursor) => {
+                self.cursor = Some(cursor.min(oldest));
+                self.playing = false;
+            }
+            Action::Play => self.playing = self.cursor.is_some_and(|cursor| cursor > 0),
+            Action::Pause => self.playing = false,
+            Action::StepForward | Action::Tick => {
+                if let Some(cursor) = &mut self.cursor {
+                    *cursor = cursor.saturating_sub(1);
+                    self.playing &= *cursor > 0;
+                }
+            }
+            Action::StepBack => {
+                if let Some(cursor) = &mut self.cursor {
+                    *cursor = (*cursor + 1).min(oldest);
+                }
+            }
+            Action::Speed(speed) => self.speed = speed.max(f32::EPSILON),
+            Action::Exit => {}
+        }
+    }
+
+    /// Index in the whole series of the candle at `index` among the revealed
+    /// ones.
+    pub fn series_index(&self, index: usize) -> usize {
+        index + self.cursor.unwrap_or(0)
+    }
+
+    /// The part of a newest-first series that has been revealed so far.
+    pub fn visible<'a>(&self, candles: &'a [Candle]) -> &'a [Candle] {
+        match self.cursor {
+            Some(cursor) => &candles[cursor.min(candles.len().saturating_sub(1))..],
+            None => candles,
+        }
+    }
+
+    /// Ticks at the playback speed while playing.
+    pub fn subscription(&self) -> Subscription<Action> {
+        if self.playing {
+            iced::time::every(Duration::from_secs_f32(1.0 / self.speed)).map(|_| Action::Tick)
+        } else {
+            Subscription::none()
+        }
+    }
+
+    /// The speed following the current one in [`SPEEDS`].
+    pub fn next_speed(&self) -> f32 {
+        SPEEDS
+            .iter()
+            .copied()
+            .find(|speed| *speed > self.speed)
+            .unwrap_or(SPEEDS[0])
+    }
+}
+
+/// Buttons of the replay control bar drawn over the chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    StepBack,
+    PlayPause,
+    StepForward,
+    Speed,
+    Exit,
+}
+
+impl Control {
+    pub const ALL: [Control; 5] = [
+        Control::StepBack,
+        Control::PlayPause,
+        Control::StepForward,
+        Control::Speed,
+        Control::Exit,
+    ];
+
+    const SIZE: Size = Size::new(36.0, 24.0);
+    const MARGIN: f32 = 8.0;
+
+    /// Bounds of the button, relative to the chart bounds.
+    pub fn bounds(self) -> Rectangle {
+        let index = Self::ALL
+            .iter()
+            .position(|control| *control == self)
+            .unwrap_or(0);
+
+        Rectangle::new(
+            Point::new(
+                Self::MARGIN + index as f32 * (Self::SIZE.width + 4.0),
+                Self::MARGIN,
+            ),
+            Self::SIZE,
+        )
+    }
+
+    pub fn at(position: Point) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|control| control.bounds().contains(position))
+    }
+
+    pub fn label(self, replay: &Replay) -> String {
+        match self {
+            Control::StepBack => "|<".to_owned(),
+            Control::PlayPause if replay.playing => "||".to_owned(),
+            Control::PlayPause => ">".to_owned(),
+            Control::StepForward => ">|".to_owned(),
+            Control::Speed => format!("{}x", replay.speed),
+            Control::Exit => "x".to_owned(),
+        }
+    }
+
+    pub fn action(self, replay: &Replay) -> Action {
+        match self {
+            Control::StepBack => Action::StepBack,
+            Control::PlayPause if replay.playing => Action::Pause,
+            Control::PlayPause => Action::Play,
+            Control::StepForward => Action::StepForward,
+            Control::Speed => Action::Speed(replay.next_speed()),
+            Control::Exit => Action::Exit,
+        }
+    }
+}
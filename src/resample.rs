@@ -0,0 +1,113 @@
+//! Merging candles into larger timeframes.
+
+use crate::{candle::Candle, time::Interval};
+
+/// Offset of an exchange time zone from UTC.
+#[derive(Debug, Clone, Copy)]
+pub enum UtcOffset {
+    /// The same offset all year, in milliseconds.
+    Fixed(i64),
+    /// The offset in milliseconds at a UTC time, following daylight saving
+    /// changes, e.g. backed by a time zone database.
+    Zone(fn(i64) -> i64),
+}
+
+impl UtcOffset {
+    /// Offset in milliseconds at the UTC `time`.
+    pub fn at(self, time: i64) -> i64 {
+        match self {
+            Self::Fixed(offset) => offset,
+            Self::Zone(offset) => offset(time),
+        }
+    }
+}
+
+impl Default for UtcOffset {
+    fn default() -> Self {
+        Self::Fixed(0)
+    }
+}
+
+impl PartialEq for UtcOffset {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Fixed(a), Self::Fixed(b)) => a == b,
+            (Self::Zone(a), Self::Zone(b)) => std::ptr::fn_addr_eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for UtcOffset {}
+
+/// Where trading days begin, used to align daily and longer buckets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Session {
+    /// Offset of the exchange time zone from UTC.
+    pub utc_offset: UtcOffset,
+    /// Local time of day at which a trading day opens, in milliseconds after
+    /// midnight. Negative values open the day on the previous evening, e.g.
+    /// `-7 * HOUR` for a 17:00 open.
+    pub day_start: i64,
+}
+
+impl Session {
+    pub const UTC: Self = Self {
+        utc_offset: UtcOffset::Fixed(0),
+        day_start: 0,
+    };
+
+    /// A session at a fixed offset from UTC, in milliseconds.
+    pub fn new(utc_offset: i64, day_start: i64) -> Self {
+        Self {
+            utc_offset: UtcOffset::Fixed(utc_offset),
+            day_start,
+        }
+    }
+
+    /// A session in a time zone whose offset from UTC, in milliseconds,
+    /// `utc_offset` gives at a UTC time.
+    pub fn zoned(utc_offset: fn(i64) -> i64, day_start: i64) -> Self {
+        Self {
+            utc_offset: UtcOffset::Zone(utc_offset),
+            day_start,
+        }
+    }
+
+    /// Start of the `interval` bucket containing `time` in this session.
+    pub fn floor(self, interval: Interval, time: i64) -> i64 {
+        let local = interval.floor(time + self.utc_offset.at(time) - self.day_start);
+        // The offset at the start of the bucket may differ from the one at
+        // `time` when daylight saving changed in between.
+        let start = local + self.day_start - self.utc_offset.at(time);
+        local + self.day_start - self.utc_offset.at(start)
+    }
+}
+
+/// Merges a newest-first series into `interval` buckets aligned to `session`.
+///
+/// Each bucket opens at its oldest candle's open, closes at its newest
+/// candle's close and sums the volume. The result is newest first.
+pub fn resample(candles: &[Candle], interval: Interval, session: Session) -> Vec<Candle> {
+    let mut resampled: Vec<Candle> = Vec::new();
+
+    for candle in candles.iter().rev() {
+        let start = session.floor(interval, candle.time);
+
+        match resampled.last_mut() {
+            Some(bucket) if bucket.time == start => {
+                bucket.high = bucket.high.max(candle.high);
+                bucket.low = bucket.low.min(candle.low);
+                bucket.close = candle.close;
+                bucket.volume += candle.volume;
+            }
+            _ => resampled.push(Candle {
+                time: start,
+                ..*candle
+            }),
+        }
+    }
+
+    resampled.reverse();
+    resampled
+}
@@ -0,0 +1,147 @@
+//! Asynchronous candle sources for charts that page in their history.
+
+use std::{path::PathBuf, sync::Arc};
+
+use futures::{FutureExt, future::BoxFuture, lock::Mutex};
+
+use crate::{
+    candle::Candle,
+    loader::{Loaded, csv::CsvLoader, json::JsonLoader},
+};
+
+/// A page of history asked for by the chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryRequest {
+    /// Only candles opening strictly before this time. `None` asks for the
+    /// latest candles.
+    pub before: Option<i64>,
+    /// Maximum number of candles in the page.
+    pub limit: usize,
+}
+
+pub trait DataSource: Send + Sync {
+    /// Fetches a newest-first page of candles. An empty page means there is
+    /// no more history.
+    fn history(&self, request: HistoryRequest) -> BoxFuture<'static, anyhow::Result<Vec<Candle>>>;
+}
+
+/// Merges a newest-first `page` into `candles`, replacing candles that share
+/// an open time.
+pub fn merge(candles: &mut Vec<Candle>, page: Vec<Candle>) {
+    let existing = std::mem::replace(candles, page);
+    candles.extend(existing);
+    candles.sort_by_key(|candle| std::cmp::Reverse(candle.time));
+    candles.dedup_by_key(|candle| candle.time);
+}
+
+fn page(candles: &[Candle], request: HistoryRequest) -> Vec<Candle> {
+    let start = match request.before {
+        Some(before) => candles.partition_point(|candle| candle.time >= before),
+        None => 0,
+    };
+
+    candles
+        .iter()
+        .skip(start)
+        .take(request.limit)
+        .copied()
+        .collect()
+}
+
+/// Serves pages out of a series held in memory.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource {
+    candles: Arc<Vec<Candle>>,
+}
+
+impl MemorySource {
+    pub fn new(mut candles: Vec<Candle>) -> Self {
+        candles.sort_by_key(|candle| std::cmp::Reverse(candle.time));
+        Self {
+            candles: Arc::new(candles),
+        }
+    }
+}
+
+impl DataSource for MemorySource {
+    fn history(&self, request: HistoryRequest) -> BoxFuture<'static, anyhow::Result<Vec<Candle>>> {
+        futures::future::ready(Ok(page(&self.candles, request))).boxed()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Format {
+    Csv(CsvLoader),
+    Json(JsonLoader),
+}
+
+/// Serves pages out of a local CSV, TSV or JSON file, read on first use.
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    path: PathBuf,
+    format: Format,
+    /// The candles once read, locked while reading them so that concurrent
+    /// requests read the file once.
+    candles: Arc<Mutex<Option<Arc<Vec<Candle>>>>>,
+}
+
+impl FileSource {
+    /// Picks the loader from the file extension, defaulting to CSV.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let format = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Format::Json(JsonLoader::new()),
+            Some("tsv") => Format::Csv(CsvLoader::tsv()),
+            _ => Format::Csv(CsvLoader::new()),
+        };
+
+        Self::with_format(path, format)
+    }
+
+    pub fn csv(path: impl Into<PathBuf>, loader: CsvLoader) -> Self {
+        Self::with_format(path.into(), Format::Csv(loader))
+    }
+
+    pub fn json(path: impl Into<PathBuf>, loader: JsonLoader) -> Self {
+        Self::with_format(path.into(), Format::Json(loader))
+    }
+
+    fn with_format(path: PathBuf, format: Format) -> Self {
+        Self {
+            path,
+            format,
+            candles: Arc::default(),
+        }
+    }
+}
+
+impl DataSource for FileSource {
+    fn history(&self, request: HistoryRequest) -> BoxFuture<'static, anyhow::Result<Vec<Candle>>> {
+        let source = self.clone();
+
+        async move {
+            let mut cached = source.candles.lock().await;
+
+            let candles = match &*cached {
+                Some(candles) => candles.clone(),
+                None => {
+                    let Loaded { candles, errors } = match &source.format {
+                        Format::Csv(loader) => loader.load(&source.path).await?,
+                        Format::Json(loader) => loader.load(&source.path).await?,
+                    };
+
+                    for error in errors {
+                        tracing::warn!("{}: {error}", source.path.display());
+                    }
+
+                    let candles = Arc::new(candles);
+                    *cached = Some(candles.clone());
+                    candles
+                }
+            };
+
+            Ok(page(&candles, request))
+        }
+        .boxed()
+    }
+}
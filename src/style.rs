@@ -11,10 +11,83 @@ pub struct Style {
 
     pub axis_color: Color,
 
+    /// Color of trend lines and other drawings.
+    pub drawing: Color,
+
     pub candle_width: f32,
     pub candle_spacing: f32,
 }
 
+/// Per-chart changes to the theme [`Style`], left unset to keep the theme.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct StyleOverrides {
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    pub background: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    pub bullish: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    pub bearish: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    pub crosshair: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    pub axis_color: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    pub drawing: Option<Color>,
+    pub candle_width: Option<f32>,
+    pub candle_spacing: Option<f32>,
+}
+
+impl StyleOverrides {
+    pub fn apply(&self, style: Style) -> Style {
+        Style {
+            background: self.background.unwrap_or(style.background),
+            bullish: self.bullish.unwrap_or(style.bullish),
+            bearish: self.bearish.unwrap_or(style.bearish),
+            crosshair: self.crosshair.unwrap_or(style.crosshair),
+            axis_color: self.axis_color.unwrap_or(style.axis_color),
+            drawing: self.drawing.unwrap_or(style.drawing),
+            candle_width: self.candle_width.unwrap_or(style.candle_width),
+            candle_spacing: self.candle_spacing.unwrap_or(style.candle_spacing),
+        }
+    }
+}
+
+/// Serializes optional colors as `#rrggbbaa` strings.
+#[cfg(feature = "serde")]
+pub(crate) mod hex {
+    use iced::Color;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match color {
+            Some(color) => {
+                let [r, g, b, a] = color.into_rgba8();
+                serializer.serialize_some(&format!("#{r:02x}{g:02x}{b:02x}{a:02x}"))
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|hex| {
+                hex.parse()
+                    .map_err(|_| D::Error::custom(format!("invalid color `{hex}`")))
+            })
+            .transpose()
+    }
+}
+
 pub trait Catalog {
     type Class<'a>;
 
@@ -44,6 +117,7 @@ pub fn default(_theme: &Theme) -> Style {
         bearish: Color::from_rgb(0.95, 0.21, 0.27),
         crosshair: Color::from_rgb(0.3, 0.3, 0.3),
         axis_color: Color::from_rgb(0.72, 0.72, 0.72),
+        drawing: Color::from_rgb(0.16, 0.49, 0.95),
         candle_width: 4.0,
         candle_spacing: 10.0,
     }
@@ -0,0 +1,97 @@
+use iced::{
+    Color, Point, Size,
+    widget::canvas::{self, LineDash, Path, Stroke},
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub width: f32,
+    pub color: Color,
+    pub dash: &'a [f32],
+}
+
+impl<'a> Line<'a> {
+    pub fn solid(width: f32, color: Color) -> Self {
+        Self {
+            width,
+            color,
+            dash: &[],
+        }
+    }
+
+    pub fn dashed(width: f32, color: Color, dash: &'a [f32]) -> Self {
+        Self { width, color, dash }
+    }
+}
+
+/// Drawing primitives used by [`CandleRenderer`](crate::renderer::CandleRenderer),
+/// so the same draw code can target a canvas frame or a vector writer.
+pub trait Surface {
+    fn size(&self) -> Size;
+
+    fn fill_rectangle(&mut self, top_left: Point, size: Size, color: Color);
+
+    fn stroke_line(&mut self, from: Point, to: Point, line: Line<'_>);
+
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color);
+
+    /// Fills the closed shape through `points`.
+    fn fill_polygon(&mut self, points: &[Point], color: Color);
+
+    fn fill_text(&mut self, content: String, position: Point, size: f32, color: Color);
+}
+
+impl Surface for canvas::Frame {
+    fn size(&self) -> Size {
+        canvas::Frame::size(self)
+    }
+
+    fn fill_rectangle(&mut self, top_left: Point, size: Size, color: Color) {
+        let rect = Path::rectangle(top_left, size);
+        self.fill(&rect, color);
+    }
+
+    fn stroke_line(&mut self, from: Point, to: Point, line: Line<'_>) {
+        let stroke = Stroke {
+            width: line.width,
+            line_dash: LineDash {
+                segments: line.dash,
+                offset: 0,
+            },
+            style: canvas::Style::Solid(line.color),
+            ..Stroke::default()
+        };
+
+        self.stroke(&Path::line(from, to), stroke);
+    }
+
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color) {
+        self.fill(&Path::circle(center, radius), color);
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+
+        let polygon = Path::new(|builder| {
+            builder.move_to(*first);
+            for point in rest {
+                builder.line_to(*point);
+            }
+            builder.close();
+        });
+        self.fill(&polygon, color);
+    }
+
+    fn fill_text(&mut self, content: String, position: Point, size: f32, color: Color) {
+        let text = canvas::Text {
+            content,
+            position,
+            size: size.into(),
+            color,
+            ..Default::default()
+        };
+        canvas::Frame::fill_text(self, text);
+    }
+}
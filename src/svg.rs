@@ -0,0 +1,260 @@
+use std::fmt::Write;
+
+use iced::{Color, Point, Rectangle, Size, mouse::Cursor};
+
+use crate::{
+    alert::{Alert, Monitor},
+    candle::Candle,
+    drawing::Drawings,
+    marker::Marker,
+    order::OrderLine,
+    renderer::CandleRenderer,
+    style::{Style, StyleOverrides},
+    surface::{Line, Surface},
+    viewport::{Projection, ViewportManager},
+};
+
+/// A [`Surface`] that writes every primitive as an SVG element.
+#[derive(Debug, Clone)]
+pub struct SvgSurface {
+    size: Size,
+    body: String,
+}
+
+impl SvgSurface {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            body: String::new(),
+        }
+    }
+
+    pub fn finish(self) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n{body}</svg>\n",
+            w = self.size.width,
+            h = self.size.height,
+            body = self.body,
+        )
+    }
+}
+
+fn color(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(content: &str) -> String {
+    let mut escaped = String::with_capacity(content.len());
+    for c in content.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Surface for SvgSurface {
+    fn size(&self) -> Size {
+        self.size
+    }
+
+    fn fill_rectangle(&mut self, top_left: Point, size: Size, fill: Color) {
+        // Candle bodies may be built from two corners in either order.
+        let x = top_left.x.min(top_left.x + size.width);
+        let y = top_left.y.min(top_left.y + size.height);
+
+        let _ = writeln!(
+            self.body,
+            "<rect x=\"{x:.2}\" y=\"{y:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\" fill-opacity=\"{:.2}\"/>",
+            size.width.abs(),
+            size.height.abs(),
+            color(fill),
+            fill.a,
+        );
+    }
+
+    fn stroke_line(&mut self, from: Point, to: Point, line: Line<'_>) {
+        let _ = write!(
+            self.body,
+            "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"{}\" stroke-opacity=\"{:.2}\" stroke-width=\"{}\"",
+            from.x,
+            from.y,
+            to.x,
+            to.y,
+            color(line.color),
+            line.color.a,
+            line.width,
+        );
+
+        if !line.dash.is_empty() {
+            let dash: Vec<String> = line.dash.iter().map(f32::to_string).collect();
+            let _ = write!(self.body, " stroke-dasharray=\"{}\"", dash.join(" "));
+        }
+
+        self.body.push_str("/>\n");
+    }
+
+    fn fill_circle(&mut self, center: Point, radius: f32, fill: Color) {
+        let _ = writeln!(
+            self.body,
+            "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{radius:.2}\" fill=\"{}\" fill-opacity=\"{:.2}\"/>",
+            center.x,
+            center.y,
+            color(fill),
+            fill.a,
+        );
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], fill: Color) {
+        let points: Vec<String> = points
+            .iter()
+            .map(|point| format!("{:.2},{:.2}", point.x, point.y))
+            .collect();
+
+        let _ = writeln!(
+            self.body,
+            "<polygon points=\"{}\" fill=\"{}\" fill-opacity=\"{:.2}\"/>",
+            points.join(" "),
+            color(fill),
+            fill.a,
+        );
+    }
+
+    fn fill_text(&mut self, content: String, position: Point, size: f32, fill: Color) {
+        // Canvas text is positioned by its top-left corner, SVG text by its baseline.
+        let _ = writeln!(
+            self.body,
+            "<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"sans-serif\" font-size=\"{size}\" fill=\"{}\" fill-opacity=\"{:.2}\">{}</text>",
+            position.x,
+            position.y + size,
+            color(fill),
+            fill.a,
+            escape(&content),
+        );
+    }
+}
+
+/// The chart as it is seen through a viewport, with what the chart widget
+/// draws over its candles, ready to be rendered into an SVG document.
+pub struct Export<'a> {
+    candles: &'a [Candle],
+    viewport: &'a ViewportManager,
+    style: Style,
+    drawings: Option<&'a Drawings>,
+    markers: &'a [Marker],
+    orders: &'a [OrderLine],
+    alerts: &'a [Alert],
+    monitor: Option<&'a Monitor>,
+}
+
+impl<'a> Export<'a> {
+    pub fn new(candles: &'a [Candle], viewport: &'a ViewportManager, style: Style) -> Self {
+        Self {
+            candles,
+            viewport,
+            style,
+            drawings: None,
+            markers: &[],
+            orders: &[],
+            alerts: &[],
+            monitor: None,
+        }
+    }
+
+    /// Applies the style overrides of the chart, as set through its layout.
+    #[must_use]
+    pub fn overrides(mut self, overrides: StyleOverrides) -> Self {
+        self.style = overrides.apply(self.style);
+        self
+    }
+
+    #[must_use]
+    pub fn drawings(mut self, drawings: &'a Drawings) -> Self {
+        self.drawings = Some(drawings);
+        self
+    }
+
+    #[must_use]
+    pub fn markers(mut self, markers: &'a [Marker]) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    #[must_use]
+    pub fn orders(mut self, orders: &'a [OrderLine]) -> Self {
+        self.orders = orders;
+        self
+    }
+
+    #[must_use]
+    pub fn alerts(mut self, alerts: &'a [Alert]) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Fills the tags of the alerts `monitor` saw trigger on the newest
+    /// candle. Without it every alert is drawn as not triggered.
+    #[must_use]
+    pub fn monitor(mut self, monitor: &'a Monitor) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Renders the chart at `size` into an SVG document.
+    pub fn render(&self, size: Size) -> String {
+        let style = &self.style;
+        let viewport = self.viewport;
+        let bounds = Rectangle::new(Point::ORIGIN, size);
+        let window = viewport.get_window(&bounds);
+        let projection = Projection::new(viewport, self.candles, style.candle_spacing, bounds);
+        let last = self.candles.first().map(|candle| candle.close);
+
+        let mut surface = SvgSurface::new(size);
+        CandleRenderer::draw_chart(
+            &mut surface,
+            viewport,
+            style,
+            self.candles,
+            &window,
+            &bounds,
+        );
+        CandleRenderer::draw_markers(&mut surface, &projection, style, self.markers);
+        if let Some(drawings) = self.drawings {
+            CandleRenderer::draw_drawings(&mut surface, &projection, style, drawings, None);
+        }
+
+        let mut marks = self.drawings.map_or_else(Vec::new, |drawings| {
+            CandleRenderer::price_marks(style, drawings, None)
+        });
+        marks.extend(CandleRenderer::order_marks(style, self.orders, None, last));
+        CandleRenderer::draw_overlay(
+            &mut surface,
+            viewport,
+            style,
+            &Cursor::Unavailable,
+            &window,
+            &bounds,
+            &marks,
+        );
+        CandleRenderer::draw_alerts(
+            &mut surface,
+            &projection,
+            style,
+            self.alerts,
+            self.drawings,
+            self.monitor.unwrap_or(&Monitor::new()),
+        );
+        CandleRenderer::draw_order_lines(&mut surface, &projection, style, self.orders, None);
+        if let Some(drawings) = self.drawings {
+            CandleRenderer::draw_annotations(&mut surface, &projection, style, drawings, None);
+            CandleRenderer::draw_drawing_labels(&mut surface, &projection, style, drawings, None);
+        }
+
+        surface.finish()
+    }
+}
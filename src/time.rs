@@ -0,0 +1,203 @@
+//! Timestamp helpers. All times in the crate are unix milliseconds in UTC.
+
+pub const SECOND: i64 = 1_000;
+pub const MINUTE: i64 = 60 * SECOND;
+pub const HOUR: i64 = 60 * MINUTE;
+pub const DAY: i64 = 24 * HOUR;
+pub const WEEK: i64 = 7 * DAY;
+
+/// Number of days since 1970-01-01 for a proleptic Gregorian date.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of [`days_from_civil`], returning `(year, month, day)`.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Parses an ISO-8601 date or date-time such as `2024-03-01`,
+/// `2024-03-01T12:30:00Z` or `2024-03-01 12:30:00.250+02:00`.
+///
+/// A missing offset is read as UTC.
+pub fn parse_iso8601(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (date, rest) = value.split_at_checked(10)?;
+
+    let mut parts = date.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut millis = days_from_civil(year, month, day) * DAY;
+
+    let rest = match rest.strip_prefix(['T', ' ']) {
+        Some(rest) => rest,
+        None if rest.is_empty() => return Some(millis),
+        None => return None,
+    };
+
+    let offset_at = rest.find(['Z', '+', '-']).unwrap_or(rest.len());
+    let (clock, offset) = rest.split_at(offset_at);
+
+    let mut clock = clock.split(':');
+    let hour: i64 = clock.next()?.parse().ok()?;
+    let minute: i64 = clock.next()?.parse().ok()?;
+    let second: f64 = match clock.next() {
+        Some(second) => second.parse().ok()?,
+        None => 0.0,
+    };
+
+    if clock.next().is_some() || hour > 23 || minute > 59 || !(0.0..61.0).contains(&second) {
+        return None;
+    }
+
+    millis += hour * HOUR + minute * MINUTE + (second * 1000.0).round() as i64;
+
+    match offset {
+        "" | "Z" => {}
+        _ => {
+            let sign = if offset.starts_with('-') { 1 } else { -1 };
+            let offset = offset[1..].replace(':', "");
+            let (hours, minutes) = offset.split_at_checked(2)?;
+            let hours: i64 = hours.parse().ok()?;
+            let minutes: i64 = if minutes.is_empty() {
+                0
+            } else {
+                minutes.parse().ok()?
+            };
+
+            millis += sign * (hours * HOUR + minutes * MINUTE);
+        }
+    }
+
+    Some(millis)
+}
+
+/// Formats `time` as `YYYY-MM-DD`, followed by `HH:MM` when it does not
+/// fall on midnight.
+pub fn format(time: i64) -> String {
+    let (year, month, day) = civil_from_days(time.div_euclid(DAY));
+    let clock = time.rem_euclid(DAY);
+
+    if clock == 0 {
+        format!("{year:04}-{month:02}-{day:02}")
+    } else {
+        format!(
+            "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+            clock / HOUR,
+            clock % HOUR / MINUTE
+        )
+    }
+}
+
+/// Formats a span of time with its two largest units, as in `3d 4h`.
+pub fn format_duration(span: i64) -> String {
+    let span = span.abs();
+    let units = [(DAY, "d"), (HOUR, "h"), (MINUTE, "m"), (SECOND, "s")];
+
+    let parts: Vec<String> = units
+        .iter()
+        .scan(span, |rest, &(unit, suffix)| {
+            let count = *rest / unit;
+            *rest %= unit;
+            Some((count, suffix))
+        })
+        .skip_while(|&(count, _)| count == 0)
+        .take(2)
+        .filter(|&(count, _)| count > 0)
+        .map(|(count, suffix)| format!("{count}{suffix}"))
+        .collect();
+
+    if parts.is_empty() {
+        "0s".to_owned()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// A bar duration. Day and longer intervals follow the UTC calendar: weeks
+/// start on Monday and months follow calendar month lengths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Interval {
+    Seconds(u32),
+    Minutes(u32),
+    Hours(u32),
+    Days(u32),
+    Weeks(u32),
+    Months(u32),
+}
+
+impl Interval {
+    /// Approximate length in milliseconds, exact for everything but months.
+    pub fn millis(self) -> i64 {
+        match self {
+            Self::Seconds(n) => n as i64 * SECOND,
+            Self::Minutes(n) => n as i64 * MINUTE,
+            Self::Hours(n) => n as i64 * HOUR,
+            Self::Days(n) => n as i64 * DAY,
+            Self::Weeks(n) => n as i64 * WEEK,
+            Self::Months(n) => n as i64 * 30 * DAY,
+        }
+    }
+
+    /// Start of the bucket containing `time`.
+    pub fn floor(self, time: i64) -> i64 {
+        match self {
+            Self::Weeks(n) => {
+                // 1970-01-05 is the first Monday after the epoch.
+                let monday = 4 * DAY;
+                let length = n.max(1) as i64 * WEEK;
+                monday + (time - monday).div_euclid(length) * length
+            }
+            Self::Months(n) => {
+                let (year, month, _) = civil_from_days(time.div_euclid(DAY));
+                let n = n.max(1) as i64;
+                let index = (year * 12 + month as i64 - 1).div_euclid(n) * n;
+                days_from_civil(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1) * DAY
+            }
+            _ => {
+                let length = self.millis().max(1);
+                time.div_euclid(length) * length
+            }
+        }
+    }
+
+    /// Start of the bucket following the one starting at `start`.
+    pub fn next(self, start: i64) -> i64 {
+        match self {
+            Self::Months(n) => {
+                let (year, month, _) = civil_from_days(start.div_euclid(DAY));
+                let index = year * 12 + month as i64 - 1 + n.max(1) as i64;
+                days_from_civil(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1) * DAY
+            }
+            _ => start + self.millis().max(1),
+        }
+    }
+}
@@ -1,7 +1,40 @@
-use iced::{Point, Rectangle, Size, Vector, keyboard, mouse};
+use iced::{
+    Point, Rectangle, Size, Vector,
+    advanced::mouse::{Click, click},
+    keyboard, mouse,
+    time::{Duration, Instant},
+    window,
+};
 
 use iced::widget::canvas;
 
+use crate::{
+    bindings::{Bindings, WheelAction},
+    candle::{self, Candle},
+    history::Step,
+    renderer::{PRICE_SCALE_WIDTH, TIME_SCALE_HEIGHT},
+};
+
+/// Length of eased zoom and programmatic transitions.
+const TRANSITION: Duration = Duration::from_millis(200);
+/// Share of the fling velocity left after coasting for a second.
+const FRICTION: f32 = 0.02;
+/// Horizontal speed in pixels per second under which coasting stops.
+const MIN_SPEED: f32 = 10.0;
+/// Vertical speed, as a share of the visible prices per second, under which
+/// coasting stops.
+const MIN_VERTICAL_SPEED: f32 = 0.01;
+/// Seconds over which the drag speed is averaged into the fling velocity,
+/// so that a single frame without movement does not stop the fling.
+const FLING_SMOOTHING: f32 = 0.05;
+/// Longest gap between frames taken into account, so that coasting does
+/// not jump after the window was idle.
+const MAX_FRAME: f32 = 0.1;
+/// Pixels panned by the left and right arrow keys.
+const KEY_PAN: f32 = 50.0;
+/// Share of the visible prices panned by the up and down arrow keys.
+const KEY_PAN_PRICE: f32 = 0.1;
+
 #[derive(Debug, Clone, Copy, Default)]
 struct ModifierState {
     ctrl: bool,
@@ -9,6 +42,46 @@ struct ModifierState {
     alt: bool,
 }
 
+/// A scale along the edge of the chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Price,
+    Time,
+}
+
+impl Axis {
+    /// The scale under `position` in a chart of `size`.
+    pub fn at(size: Size, position: Point) -> Option<Self> {
+        if position.x >= size.width - PRICE_SCALE_WIDTH {
+            Some(Self::Price)
+        } else if position.y >= size.height - TIME_SCALE_HEIGHT {
+            Some(Self::Time)
+        } else {
+            None
+        }
+    }
+}
+
+/// The pan and zoom of a [`ViewportManager`], as saved in a
+/// [`ChartLayout`](crate::layout::ChartLayout).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Viewport {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub scale: f32,
+    pub height: f32,
+}
+
+/// An eased change between two viewports, started on the first frame
+/// after it was set.
+#[derive(Debug, Clone, Copy)]
+struct Transition {
+    from: Viewport,
+    to: Viewport,
+    start: Option<Instant>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ViewportManager {
     pub offset: Vector,
@@ -16,6 +89,21 @@ pub struct ViewportManager {
     height: f32,
     modifier: ModifierState,
     drag_state: Option<Point>,
+    /// Corners of the box being dragged to zoom into.
+    selection: Option<(Point, Point)>,
+    /// Scale being dragged and the last cursor position over it.
+    axis_drag: Option<(Axis, Point)>,
+    axis_click: Option<Click>,
+    /// Whether zooming and programmatic changes ease in and panning coasts
+    /// after a fling.
+    animated: bool,
+    transition: Option<Transition>,
+    /// Offset dragged since the last frame.
+    dragged: Vector,
+    /// Speed of the pan, in offset units per second.
+    velocity: Vector,
+    last_frame: Option<Instant>,
+    bindings: Bindings,
 }
 
 impl ViewportManager {
@@ -26,9 +114,406 @@ impl ViewportManager {
             height: 60000.0,
             modifier: ModifierState::default(),
             drag_state: None,
+            selection: None,
+            axis_drag: None,
+            axis_click: None,
+            animated: true,
+            transition: None,
+            dragged: Vector::ZERO,
+            velocity: Vector::ZERO,
+            last_frame: None,
+            bindings: Bindings::default(),
+        }
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        Viewport {
+            offset_x: self.offset.x,
+            offset_y: self.offset.y,
+            scale: self.scale,
+            height: self.height,
+        }
+    }
+
+    /// The viewport the transition under way ends at, or the current one.
+    pub fn target(&self) -> Viewport {
+        self.transition
+            .map_or_else(|| self.viewport(), |transition| transition.to)
+    }
+
+    /// Pans and zooms to `viewport`.
+    pub fn restore(&mut self, viewport: Viewport) {
+        self.offset = Vector::new(viewport.offset_x, viewport.offset_y);
+        self.scale = viewport.scale;
+        self.height = viewport.height;
+    }
+
+    /// Turns eased transitions and coasting after a fling on or off.
+    pub fn set_animated(&mut self, animated: bool) {
+        self.animated = animated;
+        if !animated {
+            self.stop();
+        }
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.animated
+    }
+
+    /// Rebinds the mouse gestures, clamping the zoom to the new limits.
+    pub fn set_bindings(&mut self, bindings: Bindings) {
+        self.bindings = bindings;
+        self.scale = bindings.clamp_zoom(self.scale);
+    }
+
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    /// Whether a transition or coasting is under way.
+    pub fn is_animating(&self) -> bool {
+        self.transition.is_some() || self.velocity != Vector::ZERO
+    }
+
+    /// Applies `change` as an eased transition, or at once when animation is
+    /// off. Changes made during a transition build on where it was going.
+    pub fn animate(&mut self, change: impl FnOnce(&mut Self)) {
+        let from = self.viewport();
+        if let Some(transition) = self.transition.take() {
+            self.restore(transition.to);
+        }
+        self.velocity = Vector::ZERO;
+
+        change(self);
+
+        let to = self.viewport();
+        if self.animated && from != to {
+            self.restore(from);
+            self.transition = Some(Transition {
+                from,
+                to,
+                start: None,
+            });
+        }
+    }
+
+    /// Pans and zooms to `viewport` through an eased transition.
+    pub fn animate_to(&mut self, viewport: Viewport) {
+        self.animate(|manager| manager.restore(viewport));
+    }
+
+    /// Stops any transition where it is, and any coasting.
+    fn stop(&mut self) {
+        self.transition = None;
+        self.velocity = Vector::ZERO;
+    }
+
+    /// Advances the transition and coasting to the frame drawn at `now`,
+    /// returning whether the viewport moved.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let elapsed = self
+            .last_frame
+            .replace(now)
+            .map_or(0.0, |last| {
+                now.saturating_duration_since(last).as_secs_f32()
+            })
+            .min(MAX_FRAME);
+
+        if let Some(transition) = &mut self.transition {
+            let start = *transition.start.get_or_insert(now);
+            let progress = (now.saturating_duration_since(start).as_secs_f32()
+                / TRANSITION.as_secs_f32())
+            .min(1.0);
+            let Transition { from, to, .. } = *transition;
+            if progress >= 1.0 {
+                self.transition = None;
+            }
+
+            // Ease out: fast at first, settling into the target.
+            let t = 1.0 - (1.0 - progress).powi(3);
+            let lerp = |from: f32, to: f32| from + (to - from) * t;
+            self.restore(Viewport {
+                offset_x: lerp(from.offset_x, to.offset_x),
+                offset_y: lerp(from.offset_y, to.offset_y),
+                scale: lerp(from.scale, to.scale),
+                height: lerp(from.height, to.height),
+            });
+            return true;
+        }
+
+        if self.drag_state.is_some() {
+            // Follow the average speed of the drag, to coast at it once
+            // released.
+            if elapsed > 0.0 {
+                let speed = self.dragged * (1.0 / elapsed);
+                let weight = 1.0 - (-elapsed / FLING_SMOOTHING).exp();
+                self.velocity = self.velocity + (speed - self.velocity) * weight;
+                self.dragged = Vector::ZERO;
+                self.settle();
+            }
+            return false;
+        }
+
+        if self.velocity == Vector::ZERO || elapsed == 0.0 {
+            return false;
+        }
+        self.offset += self.velocity * elapsed;
+        self.velocity *= FRICTION.powf(elapsed);
+        self.settle();
+        true
+    }
+
+    /// Stops coasting once too slow to notice.
+    fn settle(&mut self) {
+        if self.velocity.x.abs() < MIN_SPEED
+            && (self.velocity.y / (self.height * 2.0)).abs() < MIN_VERTICAL_SPEED
+        {
+            self.velocity = Vector::ZERO;
+        }
+    }
+
+    /// Returns to the default zoom with the newest candle at the right
+    /// edge, centered on its close.
+    pub fn reset(&mut self, candles: &[Candle]) {
+        let Self {
+            offset,
+            scale,
+            height,
+            ..
+        } = Self::new();
+        self.offset = Vector::new(
+            offset.x,
+            candles.first().map_or(offset.y, |candle| candle.close),
+        );
+        self.scale = scale;
+        self.height = height;
+    }
+
+    /// Zooms so that the x units from `left` to `right` and the prices from
+    /// `low` to `high` fill `bounds` left of the price scale, centering them
+    /// when the zoom limits stop the range from fitting.
+    pub fn fit(&mut self, left: f32, right: f32, low: f32, high: f32, bounds: &Rectangle) {
+        let plot = (bounds.width - PRICE_SCALE_WIDTH).max(1.0);
+        let width = (left - right).abs();
+        if width > 0.0 {
+            self.scale = self.bindings.clamp_zoom(plot / width);
+        }
+        let center = (left + right) / 2.0;
+        self.offset = Vector::new(
+            center * self.scale - bounds.width + plot / 2.0,
+            (low + high) / 2.0,
+        );
+        self.height = ((high - low).abs() / 2.0).max(f32::EPSILON);
+    }
+
+    /// Zooms onto the candles opened from `start` to `end`, fitting their
+    /// time range and their highs and lows.
+    pub fn zoom_to_range(
+        &mut self,
+        candles: &[Candle],
+        start: i64,
+        end: i64,
+        bounds: &Rectangle,
+        spacing: f32,
+    ) {
+        let (start, end) = (start.min(end), start.max(end));
+        let (Some(older), Some(newer)) = (
+            candle::index_at(candles, start),
+            candle::index_at(candles, end),
+        ) else {
+            return;
+        };
+
+        let (low, high) = candles
+            .iter()
+            .filter(|candle| (start..=end).contains(&candle.time))
+            .fold((f32::MAX, f32::MIN), |(low, high), candle| {
+                (low.min(candle.low), high.max(candle.high))
+            });
+        if low > high {
+            return;
+        }
+
+        // Leave half a candle on each side and a little room above and below.
+        let margin = (high - low) * 0.05;
+        self.fit(
+            (older + 0.5) * spacing,
+            (newer - 0.5) * spacing,
+            low - margin,
+            high + margin,
+            bounds,
+        );
+    }
+
+    /// The box being dragged to zoom into, relative to the chart bounds.
+    pub fn selection(&self) -> Option<Rectangle> {
+        let (start, end) = self.selection?;
+        Some(Rectangle::new(
+            Point::new(start.x.min(end.x), start.y.min(end.y)),
+            Size::new((end.x - start.x).abs(), (end.y - start.y).abs()),
+        ))
+    }
+
+    /// Handles dragging a box to zoom into, with Shift held or whenever
+    /// `zooming` is set. Returns whether the event was consumed and whether
+    /// the viewport changed.
+    pub fn on_box_zoom(
+        &mut self,
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+        zooming: bool,
+    ) -> (bool, bool) {
+        let canvas::Event::Mouse(event) = event else {
+            return (false, false);
+        };
+
+        match event {
+            mouse::Event::ButtonPressed(mouse::Button::Left) if zooming || self.modifier.shift => {
+                let Some(position) = cursor.position_in(bounds) else {
+                    return (false, false);
+                };
+                self.selection = Some((position, position));
+                (true, false)
+            }
+            mouse::Event::CursorMoved { .. } => {
+                let (Some((start, _)), Some(position)) =
+                    (self.selection, cursor.position_from(bounds.position()))
+                else {
+                    return (false, false);
+                };
+                self.selection = Some((start, position));
+                (true, false)
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                let Some(selection) = self.selection() else {
+                    return (false, false);
+                };
+                self.selection = None;
+
+                // Boxes too small to mean anything are ignored like clicks.
+                if selection.width < 4.0 || selection.height < 4.0 {
+                    return (true, false);
+                }
+                // An index at unit spacing is a position in x units.
+                let left = self.index_at(selection.x, &bounds, 1.0);
+                let right = self.index_at(selection.x + selection.width, &bounds, 1.0);
+                let high = self.untransform(0.0, selection.y, &bounds).y;
+                let low = self
+                    .untransform(0.0, selection.y + selection.height, &bounds)
+                    .y;
+                self.animate(|manager| manager.fit(left, right, low, high, &bounds));
+                (true, true)
+            }
+            _ => (false, false),
         }
     }
 
+    /// The scale being dragged.
+    pub fn axis_drag(&self) -> Option<Axis> {
+        self.axis_drag.map(|(axis, _)| axis)
+    }
+
+    /// Handles dragging the price scale to stretch prices and the time
+    /// scale to space out candles, and double-clicking either to reset it.
+    /// Returns whether the event was consumed and whether the viewport
+    /// changed.
+    pub fn on_axis_event(
+        &mut self,
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+        candles: &[Candle],
+        spacing: f32,
+    ) -> (bool, bool) {
+        let canvas::Event::Mouse(event) = event else {
+            return (false, false);
+        };
+
+        match event {
+            mouse::Event::ButtonPressed(mouse::Button::Left) => {
+                let Some(position) = cursor.position_in(bounds) else {
+                    return (false, false);
+                };
+                let Some(axis) = Axis::at(bounds.size(), position) else {
+                    return (false, false);
+                };
+
+                let click = Click::new(position, mouse::Button::Left, self.axis_click);
+                self.axis_click = Some(click);
+                if click.kind() == click::Kind::Double {
+                    self.axis_drag = None;
+                    self.animate(|manager| manager.reset_axis(axis, candles, &bounds, spacing));
+                    return (true, true);
+                }
+
+                self.axis_drag = Some((axis, position));
+                (true, false)
+            }
+            mouse::Event::CursorMoved { .. } => {
+                let (Some((axis, last)), Some(position)) =
+                    (self.axis_drag, cursor.position_from(bounds.position()))
+                else {
+                    return (false, false);
+                };
+
+                match axis {
+                    // Dragging down compresses the prices, up stretches them.
+                    Axis::Price => {
+                        let factor = ((position.y - last.y) / bounds.height * 2.0).exp();
+                        self.height = (self.height * factor).max(f32::EPSILON);
+                    }
+                    // Dragging right spaces the candles out, left packs them.
+                    Axis::Time => {
+                        let factor = ((position.x - last.x) / bounds.width * 2.0).exp();
+                        self.rescale(self.scale * factor);
+                    }
+                }
+
+                self.axis_drag = Some((axis, position));
+                (true, true)
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                (self.axis_drag.take().is_some(), false)
+            }
+            _ => (false, false),
+        }
+    }
+
+    /// Fits the prices to the visible candles, or returns the candles to
+    /// their default spacing.
+    fn reset_axis(&mut self, axis: Axis, candles: &[Candle], bounds: &Rectangle, spacing: f32) {
+        match axis {
+            Axis::Price => {
+                let newest = self
+                    .index_at(bounds.width, bounds, spacing)
+                    .floor()
+                    .max(0.0);
+                let oldest = self.index_at(0.0, bounds, spacing).ceil().max(0.0);
+                let (low, high) = candles
+                    .iter()
+                    .take(oldest as usize + 1)
+                    .skip(newest as usize)
+                    .fold((f32::MAX, f32::MIN), |(low, high), candle| {
+                        (low.min(candle.low), high.max(candle.high))
+                    });
+                if low <= high {
+                    let margin = (high - low) * 0.05;
+                    self.offset.y = (low + high) / 2.0;
+                    self.height = ((high - low) / 2.0 + margin).max(f32::EPSILON);
+                }
+            }
+            Axis::Time => self.rescale(Self::new().scale),
+        }
+    }
+
+    /// Sets the horizontal zoom, keeping the candle at the right edge.
+    fn rescale(&mut self, scale: f32) {
+        let old_scale = self.scale;
+        self.scale = self.bindings.clamp_zoom(scale);
+        self.offset.x *= self.scale / old_scale;
+    }
+
     pub fn on_event(
         &mut self,
         event: &canvas::Event,
@@ -38,6 +523,7 @@ impl ViewportManager {
         match event {
             canvas::Event::Mouse(mouse_event) => self.on_event_mouse(mouse_event, bounds, cursor),
             canvas::Event::Keyboard(keyboard_event) => self.on_event_keyboard(keyboard_event),
+            canvas::Event::Window(window::Event::RedrawRequested(now)) => self.tick(*now),
             _ => false,
         }
     }
@@ -49,59 +535,39 @@ impl ViewportManager {
         cursor: mouse::Cursor,
     ) -> bool {
         match event {
-            mouse::Event::ButtonPressed(mouse::Button::Left) => {
+            mouse::Event::ButtonPressed(button) if *button == self.bindings.pan_button => {
                 self.drag_state = cursor.position_in(bounds);
+                if self.drag_state.is_some() {
+                    // Grabbing the chart stops it where it is.
+                    self.stop();
+                    self.dragged = Vector::ZERO;
+                }
                 self.drag_state.is_some()
             }
-            mouse::Event::ButtonReleased(mouse::Button::Left) => {
-                self.drag_state = None;
+            mouse::Event::ButtonReleased(button) if *button == self.bindings.pan_button => {
+                if self.drag_state.take().is_some() && !self.animated {
+                    self.velocity = Vector::ZERO;
+                }
                 false
             }
             mouse::Event::CursorMoved { position: _ } => {
-                if let Some(pos) = cursor.position_in(bounds) {
-                    if let Some(last) = self.drag_state {
-                        let drag = Vector::new(
-                            pos.x - last.x,
-                            ((pos.y - last.y) / bounds.height) * self.height * 2.0,
-                        );
-                        self.offset = self.offset + drag;
-                        self.drag_state = Some(pos);
-                        return true;
-                    }
+                if let Some(pos) = cursor.position_in(bounds)
+                    && let Some(last) = self.drag_state
+                {
+                    let drag = Vector::new(
+                        pos.x - last.x,
+                        ((pos.y - last.y) / bounds.height) * self.height * 2.0,
+                    );
+                    self.offset += drag;
+                    self.dragged += drag;
+                    self.drag_state = Some(pos);
+                    return true;
                 }
                 false
             }
             mouse::Event::WheelScrolled { delta } => {
                 if let Some(pos) = cursor.position_in(bounds) {
-                    let zoom_delta = match delta {
-                        mouse::ScrollDelta::Lines { y, .. } => *y,
-                        mouse::ScrollDelta::Pixels { y, .. } => *y / 20.0,
-                    };
-
-                    if self.modifier.alt {
-                        self.height = (self.height + zoom_delta * -5000.0).max(1000.0);
-                        return true;
-                    } else if self.modifier.shift {
-                        self.offset = Vector::new(
-                            self.offset.x + 50.0 * zoom_delta * self.scale,
-                            self.offset.y,
-                        );
-                        return true;
-                    }
-
-                    let old_scale = self.scale;
-                    self.scale = (self.scale + zoom_delta * 0.1).clamp(0.1, 10.0);
-
-                    self.offset = if self.modifier.ctrl {
-                        let p = pos.x - bounds.width;
-                        Vector::new(
-                            p + (self.offset.x - p) * (self.scale / old_scale),
-                            self.offset.y,
-                        )
-                    } else {
-                        Vector::new(self.offset.x * (self.scale / old_scale), self.offset.y)
-                    };
-
+                    self.animate(|manager| manager.on_wheel(delta, pos, &bounds));
                     return true;
                 }
                 false
@@ -110,6 +576,48 @@ impl ViewportManager {
         }
     }
 
+    fn on_wheel(&mut self, delta: &mouse::ScrollDelta, pos: Point, bounds: &Rectangle) {
+        let mut zoom_delta = match delta {
+            mouse::ScrollDelta::Lines { y, .. } => *y,
+            mouse::ScrollDelta::Pixels { y, .. } => *y / 20.0,
+        };
+        if self.bindings.invert_wheel {
+            zoom_delta = -zoom_delta;
+        }
+
+        let action =
+            self.bindings
+                .wheel_action(self.modifier.ctrl, self.modifier.shift, self.modifier.alt);
+        let anchor = match action {
+            WheelAction::Nothing => return,
+            WheelAction::ScalePrice => {
+                self.height = self
+                    .bindings
+                    .clamp_height(self.height - zoom_delta * self.bindings.price_sensitivity);
+                return;
+            }
+            WheelAction::Pan => {
+                self.offset = Vector::new(
+                    self.offset.x + 50.0 * zoom_delta * self.scale,
+                    self.offset.y,
+                );
+                return;
+            }
+            WheelAction::Zoom => 0.0,
+            WheelAction::ZoomAtCursor => pos.x - bounds.width,
+        };
+
+        let old_scale = self.scale;
+        self.scale = self
+            .bindings
+            .clamp_zoom(self.scale + zoom_delta * self.bindings.zoom_sensitivity);
+
+        self.offset = Vector::new(
+            anchor + (self.offset.x - anchor) * (self.scale / old_scale),
+            self.offset.y,
+        );
+    }
+
     fn on_event_keyboard(&mut self, event: &keyboard::Event) -> bool {
         let (pressed, key) = match event {
             keyboard::Event::KeyPressed { key, .. } => (true, key),
@@ -129,6 +637,104 @@ impl ViewportManager {
         false
     }
 
+    /// The history step bound to `event`: Ctrl+Z undoes, Ctrl+Shift+Z and
+    /// Ctrl+Y redo.
+    pub fn history_step(&self, event: &canvas::Event) -> Option<Step> {
+        let canvas::Event::Keyboard(keyboard::Event::KeyPressed {
+            key: keyboard::Key::Character(key),
+            ..
+        }) = event
+        else {
+            return None;
+        };
+
+        if !self.modifier.ctrl {
+            return None;
+        }
+
+        match key.to_ascii_lowercase().as_str() {
+            "z" if self.modifier.shift => Some(Step::Redo),
+            "z" => Some(Step::Undo),
+            "y" => Some(Step::Redo),
+            _ => None,
+        }
+    }
+
+    /// Pans just enough for the candle at `index` to show between the left
+    /// edge and the price scale.
+    pub fn reveal(&mut self, index: usize, bounds: &Rectangle, spacing: f32) {
+        let width = spacing * self.scale;
+        let x = self.transform(index as f32 * spacing, 0.0, bounds).x;
+        let right = bounds.width - PRICE_SCALE_WIDTH - width;
+
+        if x > right {
+            self.offset.x -= x - right;
+        } else if x < width {
+            self.offset.x += width - x;
+        }
+    }
+
+    /// Pans or zooms for a key press: the arrow keys pan, `+` and `-` zoom,
+    /// Home and End jump to the oldest and newest candle and Page Up and
+    /// Page Down pan a chart width back and forth.
+    pub fn on_key(
+        &mut self,
+        event: &canvas::Event,
+        candles: &[Candle],
+        bounds: &Rectangle,
+        spacing: f32,
+    ) -> bool {
+        let canvas::Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) = event else {
+            return false;
+        };
+        // Leave Ctrl and Alt shortcuts to the application.
+        if self.modifier.ctrl || self.modifier.alt {
+            return false;
+        }
+
+        let page = bounds.width - PRICE_SCALE_WIDTH;
+        let zoom = self.bindings.zoom_sensitivity;
+        let change: Box<dyn FnOnce(&mut Self)> = match key.as_ref() {
+            keyboard::Key::Named(keyboard::key::Named::ArrowLeft) => {
+                Box::new(|manager| manager.offset.x += KEY_PAN)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowRight) => {
+                Box::new(|manager| manager.offset.x -= KEY_PAN)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowUp) => {
+                Box::new(|manager| manager.offset.y += manager.height * 2.0 * KEY_PAN_PRICE)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
+                Box::new(|manager| manager.offset.y -= manager.height * 2.0 * KEY_PAN_PRICE)
+            }
+            keyboard::Key::Named(keyboard::key::Named::PageUp) => {
+                Box::new(move |manager| manager.offset.x += page)
+            }
+            keyboard::Key::Named(keyboard::key::Named::PageDown) => {
+                Box::new(move |manager| manager.offset.x -= page)
+            }
+            keyboard::Key::Named(keyboard::key::Named::Home) => {
+                let oldest = candles.len().saturating_sub(1) as f32 * spacing;
+                Box::new(move |manager| {
+                    manager.offset.x = (oldest * manager.scale - bounds.width).max(0.0);
+                })
+            }
+            keyboard::Key::Named(keyboard::key::Named::End) => {
+                Box::new(|manager| manager.offset.x = 0.0)
+            }
+            keyboard::Key::Character("+" | "=") => {
+                Box::new(move |manager| manager.rescale(manager.scale + zoom))
+            }
+            keyboard::Key::Character("-" | "_") => {
+                Box::new(move |manager| manager.rescale(manager.scale - zoom))
+            }
+            _ => return false,
+        };
+
+        self.animate(change);
+        true
+    }
+
     pub fn transform_point(&self, point: Point, bounds: &Rectangle) -> Point {
         self.transform(point.x, point.y, bounds)
     }
@@ -147,6 +753,23 @@ impl ViewportManager {
         )
     }
 
+    /// Fractional candle index under the horizontal screen position `x`.
+    pub fn index_at(&self, x: f32, bounds: &Rectangle, spacing: f32) -> f32 {
+        (bounds.width - x + self.offset.x) / (self.scale * spacing)
+    }
+
+    /// Open time of the point under the right edge of the chart.
+    pub fn anchor_time(&self, candles: &[Candle], spacing: f32) -> Option<i64> {
+        candle::time_at(candles, self.offset.x / (self.scale * spacing))
+    }
+
+    /// Pans horizontally so that `time` sits under the right edge of the chart.
+    pub fn anchor_to(&mut self, candles: &[Candle], time: i64, spacing: f32) {
+        if let Some(index) = candle::index_at(candles, time) {
+            self.offset.x = index * spacing * self.scale;
+        }
+    }
+
     pub fn get_window(&self, bounds: &Rectangle) -> Rectangle {
         let pos0 = self.untransform(bounds.width, bounds.height, bounds);
         let pos1 = self.untransform(0.0, 0.0, bounds);
@@ -156,3 +779,49 @@ impl ViewportManager {
         Rectangle::new(pos0, size)
     }
 }
+
+/// Maps between positions in the chart bounds and data coordinates, so
+/// that anything placed by time and price follows the candles as the
+/// viewport pans and zooms.
+#[derive(Debug, Clone, Copy)]
+pub struct Projection<'a> {
+    pub viewport: &'a ViewportManager,
+    pub candles: &'a [Candle],
+    pub spacing: f32,
+    pub bounds: Rectangle,
+}
+
+impl<'a> Projection<'a> {
+    pub fn new(
+        viewport: &'a ViewportManager,
+        candles: &'a [Candle],
+        spacing: f32,
+        bounds: Rectangle,
+    ) -> Self {
+        Self {
+            viewport,
+            candles,
+            spacing,
+            bounds,
+        }
+    }
+
+    /// Position of `time` and `price` relative to the chart bounds.
+    pub fn to_screen(&self, time: i64, price: f32) -> Option<Point> {
+        let index = candle::index_at(self.candles, time)?;
+
+        Some(
+            self.viewport
+                .transform(index * self.spacing, price, &self.bounds),
+        )
+    }
+
+    /// Time and price under `point`, relative to the chart bounds.
+    pub fn to_data(&self, point: Point) -> Option<(i64, f32)> {
+        let index = self.viewport.index_at(point.x, &self.bounds, self.spacing);
+        let time = candle::time_at(self.candles, index)?;
+        let price = self.viewport.untransform(point.x, point.y, &self.bounds).y;
+
+        Some((time, price))
+    }
+}
@@ -1,33 +1,90 @@
+use std::cell::Cell;
+
 use iced::{
-    Element, Event, Length, Point, Rectangle, Renderer, Size,
+    Element, Event, Length, Point, Rectangle, Renderer, Size, Vector,
     advanced::{
         Clipboard, Layout, Renderer as _, Shell, Widget,
         graphics::geometry::Renderer as _,
         layout::{Limits, Node},
-        renderer,
+        mouse::{Click, click},
+        overlay, renderer,
         widget::{
             Tree,
             tree::{self, Tag},
         },
     },
+    keyboard,
     mouse::{self, Cursor},
     widget::canvas::Cache,
+    window,
+};
+
+use crate::{
+    alert::{Alert, Monitor, Trigger},
+    bindings::Bindings,
+    candle::Candle,
+    drawing::{self, Drawings, Editor, Tool},
+    history::Step,
+    hit::CandleHit,
+    layout::ChartLayout,
+    marker::Marker,
+    menu::{Item, ItemsFn, Menu},
+    order::{self, OrderLine},
+    renderer::{CandleRenderer, PRICE_SCALE_WIDTH},
+    replay::{self, Control, Replay},
+    source::HistoryRequest,
+    style::{Catalog, StyleOverrides},
+    time::Interval,
+    viewport::{Axis, Projection, Viewport, ViewportManager},
 };
 
-use crate::{candle::Candle, renderer::CandleRenderer, style::Catalog, viewport::ViewportManager};
+/// Smallest page of history requested when panning past the oldest candle.
+const MIN_HISTORY_PAGE: usize = 100;
+/// Distance in pixels the cursor may move between press and release for a
+/// click.
+const CLICK_DISTANCE: f32 = 4.0;
 
-pub struct CandleChart<Theme>
+pub struct CandleChart<'a, Message, Theme>
 where
     Theme: Catalog,
 {
     width: Length,
     height: Length,
     class: Theme::Class<'static>,
+    overrides: StyleOverrides,
 
     candles: Vec<Candle>,
+    interval: Option<Interval>,
+    loading: bool,
+    replay: Option<Replay>,
+    drawings: Option<&'a Drawings>,
+    markers: &'a [Marker],
+    orders: &'a [OrderLine],
+    alerts: &'a [Alert],
+    tool: Tool,
+    magnet: bool,
+    stepping: bool,
+    viewport: Option<Viewport>,
+    animated: bool,
+    bindings: Bindings,
+
+    on_viewport_change: Option<Box<dyn Fn(ViewportManager, Size) -> Message + 'a>>,
+    on_layout_change: Option<Box<dyn Fn(ChartLayout) -> Message + 'a>>,
+    on_history_request: Option<Box<dyn Fn(HistoryRequest) -> Message + 'a>>,
+    on_replay: Option<Box<dyn Fn(replay::Action) -> Message + 'a>>,
+    on_drawing: Option<Box<dyn Fn(drawing::Action) -> Message + 'a>>,
+    on_history: Option<Box<dyn Fn(Step) -> Message + 'a>>,
+    on_order_drag: Option<Box<dyn Fn(usize, f32) -> Message + 'a>>,
+    on_alert: Option<Box<dyn Fn(Trigger) -> Message + 'a>>,
+    on_alert_add: Option<Box<dyn Fn(Alert) -> Message + 'a>>,
+    on_hover: Option<Box<dyn Fn(CandleHit) -> Message + 'a>>,
+    on_click: Option<Box<dyn Fn(CandleHit) -> Message + 'a>>,
+    on_double_click: Option<Box<dyn Fn(CandleHit) -> Message + 'a>>,
+    on_right_click: Option<Box<dyn Fn(CandleHit) -> Message + 'a>>,
+    context_menu: Option<ItemsFn<'a, Message>>,
 }
 
-impl<Theme> CandleChart<Theme>
+impl<'a, Message, Theme> CandleChart<'a, Message, Theme>
 where
     Theme: Catalog,
 {
@@ -36,8 +93,37 @@ where
             width: Length::Fill,
             height: Length::Fill,
             class: Theme::default(),
+            overrides: StyleOverrides::default(),
 
             candles,
+            interval: None,
+            loading: false,
+            replay: None,
+            drawings: None,
+            markers: &[],
+            orders: &[],
+            alerts: &[],
+            tool: Tool::Select,
+            magnet: false,
+            stepping: false,
+            viewport: None,
+            animated: true,
+            bindings: Bindings::default(),
+
+            on_viewport_change: None,
+            on_layout_change: None,
+            on_history_request: None,
+            on_replay: None,
+            on_drawing: None,
+            on_history: None,
+            on_order_drag: None,
+            on_alert: None,
+            on_alert_add: None,
+            on_hover: None,
+            on_click: None,
+            on_double_click: None,
+            on_right_click: None,
+            context_menu: None,
         }
     }
 
@@ -52,9 +138,530 @@ where
         self.height = height.into();
         self
     }
+
+    /// Overrides parts of the theme style for this chart.
+    #[must_use]
+    pub fn style_overrides(mut self, overrides: StyleOverrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Pans and zooms to `viewport` whenever it changes between two views.
+    #[must_use]
+    pub fn viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    /// Eases zooming and viewport changes in and lets panning coast after a
+    /// fling. On by default.
+    #[must_use]
+    pub fn animated(mut self, animated: bool) -> Self {
+        self.animated = animated;
+        self
+    }
+
+    /// Rebinds the mouse gestures panning and zooming the chart.
+    #[must_use]
+    pub fn bindings(mut self, bindings: Bindings) -> Self {
+        self.bindings = bindings;
+        self
+    }
+
+    /// Loads the drawings, interval, viewport and style overrides of
+    /// `layout`.
+    #[must_use]
+    pub fn layout(mut self, layout: &'a ChartLayout) -> Self {
+        self.drawings = Some(&layout.drawings);
+        self.interval = layout.interval.or(self.interval);
+        self.viewport = layout.viewport;
+        self.overrides = layout.style;
+        self
+    }
+
+    /// Sets the interval of the candles. When it changes between two views,
+    /// the viewport is panned to keep the same point in time in view.
+    #[must_use]
+    pub fn interval(mut self, interval: Interval) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Shows a loading indicator at the left edge of the loaded history.
+    #[must_use]
+    pub fn loading(mut self, loading: bool) -> Self {
+        self.loading = loading;
+        self
+    }
+
+    /// Puts the chart in replay mode, hiding candles newer than the replay
+    /// cursor and showing the replay controls.
+    #[must_use]
+    pub fn replay(mut self, replay: Replay) -> Self {
+        self.replay = Some(replay);
+        self
+    }
+
+    /// Sets the message produced by the replay controls and by picking the
+    /// replay start point on the chart.
+    #[must_use]
+    pub fn on_replay(mut self, on_replay: impl Fn(replay::Action) -> Message + 'a) -> Self {
+        self.on_replay = Some(Box::new(on_replay));
+        self
+    }
+
+    /// Draws `drawings` on top of the candles, anchored to their time and
+    /// price.
+    #[must_use]
+    pub fn drawings(mut self, drawings: &'a Drawings) -> Self {
+        self.drawings = Some(drawings);
+        self
+    }
+
+    /// Shows `markers` at their candles, with a tooltip on hover.
+    #[must_use]
+    pub fn markers(mut self, markers: &'a [Marker]) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    /// Draws order and position `lines` across the chart, with their price on
+    /// the price scale.
+    #[must_use]
+    pub fn orders(mut self, lines: &'a [OrderLine]) -> Self {
+        self.orders = lines;
+        self
+    }
+
+    /// Sets the message produced when the user drops a draggable order line,
+    /// carrying its index and new price. Without it the lines cannot be
+    /// dragged.
+    #[must_use]
+    pub fn on_order_drag(mut self, on_order_drag: impl Fn(usize, f32) -> Message + 'a) -> Self {
+        self.on_order_drag = Some(Box::new(on_order_drag));
+        self
+    }
+
+    /// Watches `alerts` on the newest candle and tags their levels beside the
+    /// price scale.
+    #[must_use]
+    pub fn alerts(mut self, alerts: &'a [Alert]) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Sets the message produced when the newest candle, appended or
+    /// updated since the last view, triggers one of the alerts.
+    #[must_use]
+    pub fn on_alert(mut self, on_alert: impl Fn(Trigger) -> Message + 'a) -> Self {
+        self.on_alert = Some(Box::new(on_alert));
+        self
+    }
+
+    /// Sets the message produced when the user adds an alert from the
+    /// context menu. Without it the menu cannot add alerts.
+    #[must_use]
+    pub fn on_alert_add(mut self, on_alert_add: impl Fn(Alert) -> Message + 'a) -> Self {
+        self.on_alert_add = Some(Box::new(on_alert_add));
+        self
+    }
+
+    /// Sets the tool used when pressing on the chart.
+    #[must_use]
+    pub fn tool(mut self, tool: Tool) -> Self {
+        self.tool = tool;
+        self
+    }
+
+    /// Snaps the anchors placed by the drawing tools to the open, high, low
+    /// or close of the candle under the cursor.
+    #[must_use]
+    pub fn magnet(mut self, magnet: bool) -> Self {
+        self.magnet = magnet;
+        self
+    }
+
+    /// Moves the crosshair one candle at a time with the left and right
+    /// arrow keys, instead of panning, until the mouse moves.
+    #[must_use]
+    pub fn stepping(mut self, stepping: bool) -> Self {
+        self.stepping = stepping;
+        self
+    }
+
+    /// Sets the message produced when the user creates, moves, selects or
+    /// deletes a drawing. Without it the drawings cannot be edited.
+    #[must_use]
+    pub fn on_drawing(mut self, on_drawing: impl Fn(drawing::Action) -> Message + 'a) -> Self {
+        self.on_drawing = Some(Box::new(on_drawing));
+        self
+    }
+
+    /// Sets the message produced by Ctrl+Z and Ctrl+Shift+Z over the chart,
+    /// to be applied to a [`History`](crate::history::History).
+    #[must_use]
+    pub fn on_history(mut self, on_history: impl Fn(Step) -> Message + 'a) -> Self {
+        self.on_history = Some(Box::new(on_history));
+        self
+    }
+
+    /// Sets the message produced whenever the cursor moves over the chart,
+    /// carrying what lies under it.
+    #[must_use]
+    pub fn on_hover(mut self, on_hover: impl Fn(CandleHit) -> Message + 'a) -> Self {
+        self.on_hover = Some(Box::new(on_hover));
+        self
+    }
+
+    /// Sets the message produced when the left button is pressed and
+    /// released on the chart without dragging.
+    #[must_use]
+    pub fn on_click(mut self, on_click: impl Fn(CandleHit) -> Message + 'a) -> Self {
+        self.on_click = Some(Box::new(on_click));
+        self
+    }
+
+    /// Sets the message produced when the left button is pressed twice in a
+    /// row on the chart.
+    #[must_use]
+    pub fn on_double_click(mut self, on_double_click: impl Fn(CandleHit) -> Message + 'a) -> Self {
+        self.on_double_click = Some(Box::new(on_double_click));
+        self
+    }
+
+    /// Sets the message produced when the right button is pressed on the
+    /// chart.
+    #[must_use]
+    pub fn on_right_click(mut self, on_right_click: impl Fn(CandleHit) -> Message + 'a) -> Self {
+        self.on_right_click = Some(Box::new(on_right_click));
+        self
+    }
+
+    /// Opens a menu of the items returned for the hit under the cursor when
+    /// the right button is pressed on the chart. Start from
+    /// [`menu::standard`](crate::menu::standard) to keep the built-in items.
+    #[must_use]
+    pub fn context_menu(
+        mut self,
+        context_menu: impl Fn(&CandleHit) -> Vec<Item<Message>> + 'a,
+    ) -> Self {
+        self.context_menu = Some(Box::new(context_menu));
+        self
+    }
+
+    /// Sets the message produced when the chart shows past the oldest candle,
+    /// as first drawn or once panned there.
+    ///
+    /// Each oldest candle is requested once per load: when loading ends
+    /// without older candles, as after a failed fetch, the next pan asks
+    /// again. Stop setting the message once the source has no older candles.
+    #[must_use]
+    pub fn on_history_request(
+        mut self,
+        on_history_request: impl Fn(HistoryRequest) -> Message + 'a,
+    ) -> Self {
+        self.on_history_request = Some(Box::new(on_history_request));
+        self
+    }
+
+    /// Sets the message produced whenever the user pans or zooms the chart,
+    /// carrying the new viewport and the size of the chart bounds.
+    #[must_use]
+    pub fn on_viewport_change(
+        mut self,
+        on_viewport_change: impl Fn(ViewportManager, Size) -> Message + 'a,
+    ) -> Self {
+        self.on_viewport_change = Some(Box::new(on_viewport_change));
+        self
+    }
+
+    /// Sets the message produced with the layout of the chart, its current
+    /// viewport included, whenever the user pans, zooms or edits a drawing.
+    #[must_use]
+    pub fn on_layout_change(
+        mut self,
+        on_layout_change: impl Fn(ChartLayout) -> Message + 'a,
+    ) -> Self {
+        self.on_layout_change = Some(Box::new(on_layout_change));
+        self
+    }
 }
 
-impl<'a, Message, Theme> Widget<Message, Theme, Renderer> for CandleChart<Theme>
+impl<Message, Theme> CandleChart<'_, Message, Theme>
+where
+    Theme: Catalog,
+{
+    /// Candles revealed by the replay, or all of them outside of replay mode.
+    /// The viewport, history requests and hit tests all work on these.
+    fn visible_candles(&self) -> &[Candle] {
+        match &self.replay {
+            Some(replay) => replay.visible(&self.candles),
+            None => &self.candles,
+        }
+    }
+
+    /// Handles clicks on the replay controls or, while picking, on a candle.
+    fn replay_action(
+        &self,
+        state: &State,
+        event: &Event,
+        bounds: &Rectangle,
+        cursor: Cursor,
+    ) -> Option<replay::Action> {
+        let replay = self.replay.as_ref()?;
+        let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return None;
+        };
+        let position = cursor.position_in(*bounds)?;
+
+        if replay.is_picking() {
+            let spacing = state.candle_spacing.get();
+            let index = state.viewport.index_at(position.x, bounds, spacing).round();
+
+            (index >= 0.0 && (index as usize) < self.visible_candles().len())
+                .then_some(replay::Action::Start(index as usize))
+        } else {
+            Control::at(position).map(|control| control.action(replay))
+        }
+    }
+
+    /// Publishes the hover and click messages for `event` and opens the
+    /// context menu, leaving the event to be handled by the chart.
+    fn notify(
+        &self,
+        state: &mut State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        let Event::Mouse(event) = event else {
+            return;
+        };
+        let Some(position) = cursor.position_in(bounds) else {
+            state.pressed = None;
+            return;
+        };
+        let hit = || {
+            self.hit(
+                &state.viewport,
+                state.candle_spacing.get(),
+                bounds,
+                position,
+            )
+        };
+
+        match event {
+            mouse::Event::CursorMoved { .. } => {
+                if let Some(on_hover) = &self.on_hover {
+                    shell.publish(on_hover(hit()));
+                }
+            }
+            mouse::Event::ButtonPressed(mouse::Button::Left) => {
+                let click = Click::new(position, mouse::Button::Left, state.last_click);
+                state.last_click = Some(click);
+                state.pressed = Some(position);
+
+                if let Some(on_double_click) = &self.on_double_click
+                    && click.kind() == click::Kind::Double
+                {
+                    shell.publish(on_double_click(hit()));
+                }
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                if let Some(on_click) = &self.on_click
+                    && state
+                        .pressed
+                        .take()
+                        .is_some_and(|pressed| pressed.distance(position) < CLICK_DISTANCE)
+                {
+                    shell.publish(on_click(hit()));
+                }
+            }
+            // When the right button pans, only a right click without a drag
+            // opens the menu.
+            mouse::Event::ButtonPressed(mouse::Button::Right)
+                if self.bindings.pan_button == mouse::Button::Right =>
+            {
+                state.right_pressed = Some(position);
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Right)
+                if self.bindings.pan_button == mouse::Button::Right =>
+            {
+                let pressed = state.right_pressed.take();
+                if pressed.is_some_and(|pressed| pressed.distance(position) < CLICK_DISTANCE) {
+                    let hit = hit();
+                    self.right_click(state, hit, shell);
+                }
+            }
+            mouse::Event::ButtonPressed(mouse::Button::Right) => {
+                let hit = hit();
+                self.right_click(state, hit, shell);
+            }
+            _ => {}
+        }
+    }
+
+    /// Publishes a right click on `hit` and opens the context menu there.
+    fn right_click(&self, state: &mut State, hit: CandleHit, shell: &mut Shell<'_, Message>) {
+        if let Some(on_right_click) = &self.on_right_click {
+            shell.publish(on_right_click(hit));
+        }
+        if self.context_menu.is_some() {
+            state.menu = Some(hit);
+        }
+    }
+
+    /// Moves the stepping crosshair one candle for the left and right arrow
+    /// keys, starting at the candle under the cursor or the newest in view,
+    /// and pans to keep it in view. Returns the hit under it.
+    fn step(
+        &self,
+        state: &mut State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> Option<CandleHit> {
+        let Event::Keyboard(keyboard::Event::KeyPressed {
+            key: keyboard::Key::Named(key),
+            ..
+        }) = event
+        else {
+            return None;
+        };
+        let older = match key {
+            keyboard::key::Named::ArrowLeft => true,
+            keyboard::key::Named::ArrowRight => false,
+            _ => return None,
+        };
+        let candles = self.visible_candles();
+        let oldest = candles.len().checked_sub(1)?;
+        let spacing = state.candle_spacing.get();
+
+        let index = match state.crosshair {
+            Some(index) if older => (index + 1).min(oldest),
+            Some(index) => index.saturating_sub(1),
+            None => {
+                let x = cursor
+                    .position_in(bounds)
+                    .map_or(bounds.width - PRICE_SCALE_WIDTH, |position| position.x);
+                let index = state.viewport.index_at(x, &bounds, spacing).round();
+                index.clamp(0.0, oldest as f32) as usize
+            }
+        };
+        state.crosshair = Some(index);
+        state.viewport.reveal(index, &bounds, spacing);
+
+        let projection = Projection::new(&state.viewport, candles, spacing, bounds);
+        let position = crosshair_position(&projection, index)?;
+        Some(self.hit(&state.viewport, spacing, bounds, position))
+    }
+
+    /// What lies under `position`, for the application: the candle index
+    /// points into the candles the chart was given, not the replayed ones.
+    fn hit(
+        &self,
+        viewport: &ViewportManager,
+        spacing: f32,
+        bounds: Rectangle,
+        position: Point,
+    ) -> CandleHit {
+        let projection = Projection::new(viewport, self.visible_candles(), spacing, bounds);
+        let mut hit = CandleHit::at(
+            &projection,
+            position,
+            self.drawings,
+            self.markers,
+            self.orders,
+        );
+
+        if let Some(replay) = &self.replay {
+            hit.index = hit.index.map(|index| replay.series_index(index));
+        }
+        hit
+    }
+
+    /// The layout the chart shows through `viewport`, the viewport being the
+    /// end of any transition under way.
+    fn current_layout(&self, viewport: &ViewportManager) -> ChartLayout {
+        ChartLayout {
+            interval: self.interval,
+            viewport: Some(viewport.target()),
+            style: self.overrides,
+            drawings: self.drawings.cloned().unwrap_or_default(),
+        }
+    }
+
+    fn drawn(&self) -> Drawn {
+        Drawn {
+            len: self.candles.len(),
+            newest: self.candles.first().copied(),
+            oldest: self.candles.last().map(|candle| candle.time),
+            cursor: self.replay.and_then(|replay| replay.cursor),
+            loading: self.loading,
+            overrides: self.overrides,
+            drawings: self.drawings.cloned(),
+            markers: self.markers.to_vec(),
+            orders: self.orders.to_vec(),
+            alerts: self.alerts.to_vec(),
+        }
+    }
+
+    /// Follows up on a pan or zoom: re-anchors the interval, reports the new
+    /// viewport and requests older history when needed.
+    fn viewport_changed(
+        &self,
+        state: &mut State,
+        bounds: &Rectangle,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        if self.interval.is_some() {
+            let spacing = state.candle_spacing.get();
+            state.anchor = state.viewport.anchor_time(self.visible_candles(), spacing);
+        }
+        // The application may hand the viewport back, which must not restart
+        // the move it came from.
+        state.restored = Some(state.viewport.target());
+        if let Some(on_viewport_change) = &self.on_viewport_change {
+            shell.publish(on_viewport_change(state.viewport.clone(), bounds.size()));
+        }
+        if let Some(on_layout_change) = &self.on_layout_change {
+            shell.publish(on_layout_change(self.current_layout(&state.viewport)));
+        }
+        self.request_history(state, bounds, shell);
+    }
+
+    fn request_history(
+        &self,
+        state: &mut State,
+        bounds: &Rectangle,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        let (Some(on_history_request), Some(oldest)) =
+            (&self.on_history_request, self.visible_candles().last())
+        else {
+            return;
+        };
+
+        let spacing = state.candle_spacing.get();
+        let window = state.viewport.get_window(bounds);
+        let oldest_x = (self.visible_candles().len() - 1) as f32 * spacing;
+
+        if self.loading || window.x <= oldest_x || state.requested_before == Some(oldest.time) {
+            return;
+        }
+
+        state.requested_before = Some(oldest.time);
+
+        let visible = (window.width.abs() / spacing).ceil() as usize;
+        shell.publish(on_history_request(HistoryRequest {
+            before: Some(oldest.time),
+            limit: visible.max(MIN_HISTORY_PAGE),
+        }));
+    }
+}
+
+impl<'a, Message, Theme> Widget<Message, Theme, Renderer> for CandleChart<'a, Message, Theme>
 where
     Message: 'a + Clone,
     Theme: Catalog,
@@ -64,7 +671,95 @@ where
     }
 
     fn state(&self) -> tree::State {
-        tree::State::new(State::new())
+        // The first tree is built without a `diff`, so start from the chart
+        // as configured.
+        let mut state = State::new();
+        state.interval = self.interval;
+        state.drawn = self.drawn();
+        state.viewport.set_animated(self.animated);
+        state.viewport.set_bindings(self.bindings);
+        if let Some(viewport) = self.viewport {
+            state.viewport.restore(viewport);
+            state.restored = Some(viewport);
+            state.anchor = state
+                .viewport
+                .anchor_time(self.visible_candles(), state.candle_spacing.get());
+        }
+
+        tree::State::new(state)
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let state: &mut State = tree.state.downcast_mut();
+
+        if state.interval != self.interval {
+            if let Some(anchor) = state.anchor {
+                let spacing = state.candle_spacing.get();
+                state
+                    .viewport
+                    .anchor_to(self.visible_candles(), anchor, spacing);
+                state.invalidate();
+            }
+            state.interval = self.interval;
+        }
+
+        // A load that brought no older candles, failed or empty, may be
+        // retried.
+        // The cached layers hold the candles, markers, drawings and the
+        // lines beside them, drawn from the previous view.
+        let drawn = self.drawn();
+        if state.drawn != drawn {
+            state.drawn = drawn;
+            state.invalidate();
+        }
+
+        if state.loading
+            && !self.loading
+            && state.requested_before == self.candles.last().map(|candle| candle.time)
+        {
+            state.requested_before = None;
+        }
+        state.loading = self.loading;
+
+        let triggers = state
+            .alerts
+            .check(self.alerts, self.visible_candles(), self.drawings);
+        if !triggers.is_empty() {
+            state.overlay_cache.clear();
+            if self.on_alert.is_some() {
+                state.triggers.extend(triggers);
+            }
+        }
+
+        if !self.stepping {
+            state.crosshair = None;
+        }
+
+        if state.viewport.is_animated() != self.animated {
+            state.viewport.set_animated(self.animated);
+        }
+        if *state.viewport.bindings() != self.bindings {
+            state.viewport.set_bindings(self.bindings);
+        }
+
+        if let Some(viewport) = self.viewport
+            && state.restored != Some(viewport)
+        {
+            // The first viewport is the starting point, later ones are moves.
+            let first = state.restored.replace(viewport).is_none();
+
+            if state.viewport.target() != viewport {
+                if first {
+                    state.viewport.restore(viewport);
+                } else {
+                    state.viewport.animate_to(viewport);
+                }
+                state.anchor = state
+                    .viewport
+                    .anchor_time(self.visible_candles(), state.candle_spacing.get());
+                state.invalidate();
+            }
+        }
     }
 
     fn size(&self) -> Size<Length> {
@@ -75,19 +770,94 @@ where
         Node::new(limits.resolve(self.width, self.height, Size::ZERO))
     }
 
+    fn overlay<'b>(
+        &'b mut self,
+        state: &'b mut Tree,
+        layout: Layout<'b>,
+        _renderer: &Renderer,
+        _viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let wstate: &mut State = state.state.downcast_mut();
+        let hit = wstate.menu?;
+        let items = self.context_menu.as_ref()?(&hit);
+        if items.is_empty() {
+            return None;
+        }
+
+        let chart_layout = self
+            .on_layout_change
+            .as_deref()
+            .map(|on_layout_change| (self.current_layout(&wstate.viewport), on_layout_change));
+
+        Some(overlay::Element::new(Box::new(Menu {
+            items,
+            hit,
+            bounds: layout.bounds() + translation,
+            candles: self.visible_candles(),
+            state: wstate,
+            class: &self.class,
+            overrides: self.overrides,
+            on_drawing: self.on_drawing.as_deref(),
+            on_viewport_change: self.on_viewport_change.as_deref(),
+            on_alert_add: self.on_alert_add.as_deref(),
+            layout: chart_layout,
+        })))
+    }
+
     fn mouse_interaction(
         &self,
-        _state: &Tree,
+        state: &Tree,
         layout: Layout<'_>,
         cursor: Cursor,
         _viewport: &Rectangle,
         _renderer: &Renderer,
     ) -> mouse::Interaction {
-        if cursor.is_over(layout.bounds()) {
-            mouse::Interaction::Crosshair
-        } else {
-            mouse::Interaction::default()
+        let bounds = layout.bounds();
+        let wstate: &State = state.state.downcast_ref();
+
+        if wstate.orders.is_dragging() {
+            return mouse::Interaction::ResizingVertically;
+        }
+        if wstate.editor.is_editing() {
+            return mouse::Interaction::Grabbing;
         }
+        let Some(position) = cursor.position_in(bounds) else {
+            return mouse::Interaction::default();
+        };
+
+        match wstate
+            .viewport
+            .axis_drag()
+            .or_else(|| Axis::at(bounds.size(), position))
+        {
+            Some(Axis::Price) => return mouse::Interaction::ResizingVertically,
+            Some(Axis::Time) => return mouse::Interaction::ResizingHorizontally,
+            None => {}
+        }
+        let projection = Projection::new(
+            &wstate.viewport,
+            self.visible_candles(),
+            wstate.candle_spacing.get(),
+            bounds,
+        );
+
+        if self.on_order_drag.is_some() && order::hit(self.orders, &projection, position).is_some()
+        {
+            return mouse::Interaction::ResizingVertically;
+        }
+
+        if let Some(drawings) = self.drawings
+            && self.on_drawing.is_some()
+            && self.tool == Tool::Select
+            && drawings
+                .iter()
+                .any(|drawing| drawing.hit(&projection, position).is_some())
+        {
+            return mouse::Interaction::Pointer;
+        }
+
+        mouse::Interaction::Crosshair
     }
 
     fn update(
@@ -103,11 +873,176 @@ where
     ) {
         let bounds = layout.bounds();
         let wstate: &mut State = state.state.downcast_mut();
-        let upd = wstate.viewport.on_event(event, bounds, cursor);
-        if upd {}
-        wstate.chart_cache.clear();
-        wstate.overlay_cache.clear();
-        shell.request_redraw();
+
+        // Triggers are found when the candles change in `diff`, which has no
+        // shell to publish them.
+        if let Some(on_alert) = &self.on_alert {
+            for trigger in wstate.triggers.drain(..) {
+                shell.publish(on_alert(trigger));
+            }
+        }
+
+        self.notify(wstate, event, bounds, cursor, shell);
+
+        // History is otherwise requested on pans, which a chart first drawn
+        // or resized past its oldest candle may never get.
+        if let Event::Window(window::Event::RedrawRequested(_)) = event
+            && wstate.history_bounds != Some(bounds)
+        {
+            wstate.history_bounds = Some(bounds);
+            self.request_history(wstate, &bounds, shell);
+        }
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(_)) => {
+                wstate.focused = cursor.is_over(bounds);
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if cursor.is_over(bounds) => {
+                // The mouse takes the crosshair back from the keyboard.
+                wstate.crosshair = None;
+            }
+            _ => {}
+        }
+
+        if let Some(on_replay) = &self.on_replay
+            && let Some(action) = self.replay_action(wstate, event, &bounds, cursor)
+        {
+            shell.publish(on_replay(action));
+            shell.capture_event();
+            return;
+        }
+
+        let (captured, scaled) = wstate.viewport.on_axis_event(
+            event,
+            bounds,
+            cursor,
+            self.visible_candles(),
+            wstate.candle_spacing.get(),
+        );
+        if captured {
+            if scaled {
+                self.viewport_changed(wstate, &bounds, shell);
+            }
+            shell.capture_event();
+            wstate.invalidate();
+            shell.request_redraw();
+            return;
+        }
+
+        if let Some(on_order_drag) = &self.on_order_drag {
+            let projection = Projection::new(
+                &wstate.viewport,
+                self.visible_candles(),
+                wstate.candle_spacing.get(),
+                bounds,
+            );
+            let (dropped, captured) =
+                wstate
+                    .orders
+                    .on_event(event, &projection, cursor, self.orders);
+
+            if let Some((index, price)) = dropped {
+                shell.publish(on_order_drag(index, price));
+            }
+            if captured {
+                shell.capture_event();
+                wstate.overlay_cache.clear();
+                shell.request_redraw();
+                return;
+            }
+        }
+
+        if let (Some(drawings), Some(on_drawing)) = (self.drawings, &self.on_drawing) {
+            let projection = Projection::new(
+                &wstate.viewport,
+                self.visible_candles(),
+                wstate.candle_spacing.get(),
+                bounds,
+            );
+            let (action, captured) = wstate.editor.on_event(
+                event,
+                &projection,
+                cursor,
+                drawings,
+                self.tool,
+                self.magnet,
+            );
+
+            if let Some(action) = action {
+                if let Some(on_layout_change) = &self.on_layout_change {
+                    let mut layout = self.current_layout(&wstate.viewport);
+                    layout.drawings.perform(action.clone());
+                    shell.publish(on_layout_change(layout));
+                }
+                shell.publish(on_drawing(action));
+            }
+            if captured {
+                shell.capture_event();
+                wstate.invalidate();
+                shell.request_redraw();
+                return;
+            }
+        }
+
+        if let Some(on_history) = &self.on_history
+            && cursor.is_over(bounds)
+            && let Some(step) = wstate.viewport.history_step(event)
+        {
+            shell.publish(on_history(step));
+            shell.capture_event();
+            return;
+        }
+
+        if wstate.focused || cursor.is_over(bounds) {
+            if self.stepping
+                && let Some(hit) = self.step(wstate, event, bounds, cursor)
+            {
+                if let Some(on_hover) = &self.on_hover {
+                    shell.publish(on_hover(hit));
+                }
+                self.viewport_changed(wstate, &bounds, shell);
+                shell.capture_event();
+                wstate.invalidate();
+                shell.request_redraw();
+                return;
+            }
+
+            let spacing = wstate.candle_spacing.get();
+            if wstate
+                .viewport
+                .on_key(event, self.visible_candles(), &bounds, spacing)
+            {
+                self.viewport_changed(wstate, &bounds, shell);
+                shell.capture_event();
+                wstate.invalidate();
+                shell.request_redraw();
+                return;
+            }
+        }
+
+        let (captured, zoomed) =
+            wstate
+                .viewport
+                .on_box_zoom(event, bounds, cursor, self.tool == Tool::Zoom);
+        let upd = if captured {
+            shell.capture_event();
+            zoomed
+        } else {
+            wstate.viewport.on_event(event, bounds, cursor)
+        };
+        if upd {
+            self.viewport_changed(wstate, &bounds, shell);
+            wstate.chart_cache.clear();
+        }
+        // The crosshair follows the cursor.
+        if upd || matches!(event, Event::Mouse(_)) {
+            wstate.overlay_cache.clear();
+            shell.request_redraw();
+        }
+        // Frames keep coming only while a transition or coasting needs them.
+        if wstate.viewport.is_animating() {
+            shell.request_redraw();
+        }
     }
 
     fn draw(
@@ -122,30 +1057,119 @@ where
     ) {
         let bounds = layout.bounds();
         let wstate: &State = state.state.downcast_ref();
-        let style = theme.style(&self.class);
+        let style = self.overrides.apply(theme.style(&self.class));
+        wstate.candle_spacing.set(style.candle_spacing);
 
         let window = wstate.viewport.get_window(&bounds);
+        let candles = self.visible_candles();
+
+        let projection = Projection::new(&wstate.viewport, candles, style.candle_spacing, bounds);
 
         let chart_geometry = wstate.chart_cache.draw(renderer, bounds.size(), |frame| {
-            CandleRenderer::draw_chart(
-                frame,
-                &wstate.viewport,
-                &style,
-                &self.candles,
-                &window,
-                &bounds,
-            );
+            CandleRenderer::draw_chart(frame, &wstate.viewport, &style, candles, &window, &bounds);
+            CandleRenderer::draw_markers(frame, &projection, &style, self.markers);
+
+            if let Some(drawings) = self.drawings {
+                CandleRenderer::draw_drawings(
+                    frame,
+                    &projection,
+                    &style,
+                    drawings,
+                    wstate.editor.preview(),
+                );
+            }
         });
 
         let overlay_geometry = wstate.overlay_cache.draw(renderer, bounds.size(), |frame| {
+            let mut marks = self.drawings.map_or_else(Vec::new, |drawings| {
+                CandleRenderer::price_marks(&style, drawings, wstate.editor.preview())
+            });
+            marks.extend(CandleRenderer::order_marks(
+                &style,
+                self.orders,
+                wstate.orders.preview(),
+                candles.first().map(|candle| candle.close),
+            ));
+
+            // The stepping crosshair stands in for the cursor.
+            let crosshair = wstate
+                .crosshair
+                .and_then(|index| crosshair_position(&projection, index))
+                .map_or(cursor, |position| {
+                    Cursor::Available(position + (bounds.position() - Point::ORIGIN))
+                });
+
             CandleRenderer::draw_overlay(
                 frame,
                 &wstate.viewport,
                 &style,
-                &cursor,
+                &crosshair,
                 &window,
                 &bounds,
+                &marks,
+            );
+
+            if let Some(selection) = wstate.viewport.selection() {
+                CandleRenderer::draw_selection(frame, &style, selection);
+            }
+
+            if self.loading {
+                CandleRenderer::draw_loading(
+                    frame,
+                    &wstate.viewport,
+                    &style,
+                    candles,
+                    &window,
+                    &bounds,
+                );
+            }
+
+            CandleRenderer::draw_alerts(
+                frame,
+                &projection,
+                &style,
+                self.alerts,
+                self.drawings,
+                &wstate.alerts,
             );
+            CandleRenderer::draw_order_lines(
+                frame,
+                &projection,
+                &style,
+                self.orders,
+                wstate.orders.preview(),
+            );
+
+            if let Some(position) = cursor.position_in(bounds) {
+                CandleRenderer::draw_marker_tooltip(
+                    frame,
+                    &projection,
+                    &style,
+                    self.markers,
+                    position,
+                );
+            }
+
+            if let Some(drawings) = self.drawings {
+                CandleRenderer::draw_annotations(
+                    frame,
+                    &projection,
+                    &style,
+                    drawings,
+                    wstate.editor.preview(),
+                );
+                CandleRenderer::draw_drawing_labels(
+                    frame,
+                    &projection,
+                    &style,
+                    drawings,
+                    wstate.editor.preview(),
+                );
+            }
+
+            if let Some(replay) = &self.replay {
+                CandleRenderer::draw_replay_controls(frame, &style, replay, &bounds);
+            }
         });
 
         renderer.with_translation(bounds.position() - Point::ORIGIN, |renderer| {
@@ -155,11 +1179,72 @@ where
     }
 }
 
+/// Position of the close of the candle at `index`, relative to the chart
+/// bounds.
+fn crosshair_position(projection: &Projection<'_>, index: usize) -> Option<Point> {
+    let candle = projection.candles.get(index)?;
+    Some(projection.viewport.transform(
+        index as f32 * projection.spacing,
+        candle.close,
+        &projection.bounds,
+    ))
+}
+
+/// What the cached layers were last drawn from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Drawn {
+    len: usize,
+    newest: Option<Candle>,
+    oldest: Option<i64>,
+    /// Replay cursor, hiding the candles after it.
+    cursor: Option<usize>,
+    loading: bool,
+    overrides: StyleOverrides,
+    drawings: Option<Drawings>,
+    markers: Vec<Marker>,
+    orders: Vec<OrderLine>,
+    alerts: Vec<Alert>,
+}
+
 #[derive(Default, Debug)]
 pub struct State {
     pub(crate) chart_cache: Cache,
     pub(crate) overlay_cache: Cache,
     pub(crate) viewport: ViewportManager,
+    pub(crate) interval: Option<Interval>,
+    /// Time under the right edge, kept to re-anchor on interval changes.
+    pub(crate) anchor: Option<i64>,
+    /// Candle spacing of the last drawn style, for mapping positions to
+    /// candles outside of `draw`.
+    pub(crate) candle_spacing: Cell<f32>,
+    /// Oldest candle time history was last requested for.
+    pub(crate) requested_before: Option<i64>,
+    /// Whether the chart was loading history as of the last view.
+    pub(crate) loading: bool,
+    /// Bounds history was last checked for outside of pans.
+    pub(crate) history_bounds: Option<Rectangle>,
+    pub(crate) editor: Editor,
+    pub(crate) orders: order::Dragging,
+    pub(crate) alerts: Monitor,
+    /// Alert triggers waiting for the next event to be published.
+    pub(crate) triggers: Vec<Trigger>,
+    /// Where the left button was pressed, to tell clicks from drags.
+    pub(crate) pressed: Option<Point>,
+    /// Where the right button was pressed while it pans the chart.
+    pub(crate) right_pressed: Option<Point>,
+    pub(crate) last_click: Option<Click>,
+    /// Hit the context menu was opened at, while it is open.
+    pub(crate) menu: Option<CandleHit>,
+    /// Whether the chart was pressed last, taking the keyboard even when
+    /// the cursor is elsewhere.
+    pub(crate) focused: bool,
+    /// Candle under the stepping crosshair, by index.
+    pub(crate) crosshair: Option<usize>,
+    /// Viewport last loaded from the chart, restored only when it changes.
+    pub(crate) restored: Option<Viewport>,
+    pub(crate) drawn: Drawn,
+    /// Times the cached layers were cleared.
+    invalidated: u64,
 }
 
 impl State {
@@ -168,16 +1253,49 @@ impl State {
             chart_cache: Cache::default(),
             overlay_cache: Cache::default(),
             viewport: ViewportManager::new(),
+            interval: None,
+            anchor: None,
+            candle_spacing: Cell::new(10.0),
+            requested_before: None,
+            loading: false,
+            history_bounds: None,
+            editor: Editor::default(),
+            orders: order::Dragging::default(),
+            alerts: Monitor::new(),
+            triggers: Vec::new(),
+            pressed: None,
+            right_pressed: None,
+            last_click: None,
+            menu: None,
+            focused: false,
+            crosshair: None,
+            restored: None,
+            drawn: Drawn::default(),
+            invalidated: 0,
         }
     }
+
+    /// Clears both cached layers, to draw them again on the next frame.
+    pub(crate) fn invalidate(&mut self) {
+        self.chart_cache.clear();
+        self.overlay_cache.clear();
+        self.invalidated += 1;
+    }
+
+    /// How many times the cached layers were cleared, for telling whether a
+    /// change of the chart reached them.
+    pub fn invalidated(&self) -> u64 {
+        self.invalidated
+    }
 }
 
-impl<'a, Message, Theme> From<CandleChart<Theme>> for Element<'a, Message, Theme, Renderer>
+impl<'a, Message, Theme> From<CandleChart<'a, Message, Theme>>
+    for Element<'a, Message, Theme, Renderer>
 where
     Theme: 'a + Catalog,
     Message: Clone + 'a,
 {
-    fn from(candle_charts: CandleChart<Theme>) -> Self {
+    fn from(candle_charts: CandleChart<'a, Message, Theme>) -> Self {
         Element::new(candle_charts)
     }
 }
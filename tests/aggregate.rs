@@ -0,0 +1,93 @@
+use iced_charts::{
+    aggregate::{Aggregator, Bars, Trade, Update},
+    time::{self, Interval},
+};
+
+fn trade(time: i64, price: f32, size: f32) -> Trade {
+    Trade { time, price, size }
+}
+
+#[test]
+fn time_bars_update_the_last_candle() {
+    let mut aggregator = Aggregator::new(Bars::Time(Interval::Minutes(1)));
+
+    assert_eq!(aggregator.push(trade(5_000, 10.0, 1.0)), Update::Opened);
+    assert_eq!(
+        aggregator.push(trade(20_000, 12.0, 2.0)),
+        Update::Updated(0)
+    );
+    assert_eq!(aggregator.push(trade(50_000, 9.0, 1.0)), Update::Updated(0));
+    assert_eq!(aggregator.push(trade(61_000, 11.0, 1.0)), Update::Opened);
+
+    let candles = aggregator.candles();
+    assert_eq!(candles.len(), 2);
+    assert_eq!(candles[0].time, time::MINUTE);
+    assert_eq!(
+        (
+            candles[1].open,
+            candles[1].high,
+            candles[1].low,
+            candles[1].close
+        ),
+        (10.0, 12.0, 9.0, 9.0)
+    );
+    assert_eq!(candles[1].volume, 4.0);
+
+    // A late trade for the previous bar widens it without moving its close.
+    assert_eq!(
+        aggregator.push(trade(59_000, 20.0, 1.0)),
+        Update::Updated(1)
+    );
+    assert_eq!(aggregator.candles()[1].high, 20.0);
+    assert_eq!(aggregator.candles()[1].close, 9.0);
+}
+
+#[test]
+fn calendar_intervals() {
+    let march_15 = time::parse_iso8601("2024-03-15T10:00:00Z").unwrap();
+
+    assert_eq!(
+        Interval::Months(1).floor(march_15),
+        time::parse_iso8601("2024-03-01").unwrap()
+    );
+    assert_eq!(
+        Interval::Months(3).next(time::parse_iso8601("2024-10-01").unwrap()),
+        time::parse_iso8601("2025-01-01").unwrap()
+    );
+    assert_eq!(
+        Interval::Weeks(1).floor(march_15),
+        time::parse_iso8601("2024-03-11").unwrap()
+    );
+}
+
+#[test]
+fn threshold_bars() {
+    let mut ticks = Aggregator::new(Bars::Tick(2));
+    ticks.extend((0..5).map(|i| trade(i, i as f32, 1.0)));
+    assert_eq!(ticks.candles().len(), 3);
+    assert_eq!(ticks.candles()[1].open, 2.0);
+    assert_eq!(ticks.candles()[1].close, 3.0);
+
+    let mut dollars = Aggregator::new(Bars::Dollar(100.0));
+    dollars.extend([
+        trade(0, 10.0, 5.0),
+        trade(1, 10.0, 6.0),
+        trade(2, 10.0, 1.0),
+    ]);
+    assert_eq!(dollars.candles().len(), 2);
+    assert_eq!(dollars.candles()[1].volume, 11.0);
+}
+
+#[test]
+fn threshold_bars_keep_same_millisecond_trades_together() {
+    let mut ticks = Aggregator::new(Bars::Tick(1));
+    assert_eq!(ticks.push(trade(5, 10.0, 1.0)), Update::Opened);
+    assert_eq!(ticks.push(trade(5, 11.0, 1.0)), Update::Updated(0));
+    assert_eq!(ticks.push(trade(6, 12.0, 1.0)), Update::Opened);
+
+    let candles = ticks.candles();
+    assert_eq!(candles.len(), 2);
+    assert_eq!((candles[1].time, candles[1].close), (5, 11.0));
+    assert_eq!(candles[1].volume, 2.0);
+    assert_eq!(candles[0].time, 6);
+}
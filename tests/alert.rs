@@ -0,0 +1,173 @@
+use iced_charts::{
+    alert::{Alert, Condition, Level, Monitor},
+    candle::Candle,
+    drawing::{Action, Anchor, Drawing, Drawings, Range, TrendLine},
+    time::DAY,
+};
+
+fn candle(day: i64, low: f32, high: f32, close: f32) -> Candle {
+    Candle {
+        time: day * DAY,
+        open: close,
+        high,
+        low,
+        close,
+        volume: 0.0,
+    }
+}
+
+/// Ten days closing at 100, newest first.
+fn history() -> Vec<Candle> {
+    (0..10)
+        .rev()
+        .map(|day| candle(day, 99.0, 101.0, 100.0))
+        .collect()
+}
+
+#[test]
+fn crossings_trigger_once_per_candle() {
+    let alerts = [
+        Alert::new(Level::Price(105.0), Condition::CrossUp),
+        Alert::new(Level::Price(105.0), Condition::CrossDown),
+        Alert::new(Level::Price(108.0), Condition::Touch).name("Touch"),
+    ];
+    let mut candles = history();
+    let mut monitor = Monitor::new();
+
+    assert!(monitor.check(&alerts, &candles, None).is_empty());
+
+    candles.insert(0, candle(10, 100.0, 106.0, 106.0));
+    let triggers = monitor.check(&alerts, &candles, None);
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].alert, alerts[0].id());
+    assert_eq!(triggers[0].time, 10 * DAY);
+    assert!(monitor.is_triggered(&alerts[0]));
+
+    // Dipping back and crossing up again on the same candle stays quiet.
+    candles[0] = candle(10, 100.0, 106.0, 104.0);
+    let triggers = monitor.check(&alerts, &candles, None);
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].alert, alerts[1].id());
+    candles[0] = candle(10, 100.0, 108.5, 106.0);
+    let triggers = monitor.check(&alerts, &candles, None);
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].alert, alerts[2].id());
+
+    // Unchanged candles are not checked again.
+    assert!(monitor.check(&alerts, &candles, None).is_empty());
+
+    candles.insert(0, candle(11, 103.0, 106.0, 104.0));
+    let triggers = monitor.check(&alerts, &candles, None);
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].alert, alerts[1].id());
+    assert!(!monitor.is_triggered(&alerts[0]));
+}
+
+#[test]
+fn channels_trigger_on_entry_and_exit() {
+    let range = Drawing::PriceRange(Range::new(
+        Anchor::new(0, 110.0),
+        Anchor::new(5 * DAY, 105.0),
+    ));
+    assert_eq!(
+        Level::of(&range),
+        Some(Level::Channel {
+            low: 105.0,
+            high: 110.0
+        })
+    );
+
+    let drawings = Drawings::from(vec![range, Drawing::VerticalLine(Anchor::new(0, 0.0))]);
+    let alerts = [
+        Alert::on(&drawings, 0, Condition::Enter).unwrap(),
+        Alert::on(&drawings, 0, Condition::Exit).unwrap(),
+    ];
+    assert_eq!(alerts[0].level, Level::Drawing(drawings.id(0).unwrap()));
+    assert!(Alert::on(&drawings, 1, Condition::Cross).is_none());
+
+    let mut candles = history();
+    let mut monitor = Monitor::new();
+    monitor.check(&alerts, &candles, Some(&drawings));
+
+    candles.insert(0, candle(10, 100.0, 107.0, 107.0));
+    let entered: Vec<_> = monitor.check(&alerts, &candles, Some(&drawings));
+    assert_eq!(entered.len(), 1);
+    assert_eq!(entered[0].alert, alerts[0].id());
+
+    candles.insert(0, candle(11, 107.0, 112.0, 112.0));
+    let exited = monitor.check(&alerts, &candles, Some(&drawings));
+    assert_eq!(exited.len(), 1);
+    assert_eq!(exited[0].alert, alerts[1].id());
+}
+
+#[test]
+fn trend_lines_are_checked_at_the_newest_candle() {
+    // Rising by one per day from 100 on day 0.
+    let mut line = TrendLine::new(Anchor::new(0, 100.0), Anchor::new(5 * DAY, 105.0));
+    let candles = history();
+
+    assert_eq!(line.price_at(&candles, 2 * DAY), Some(102.0));
+    assert_eq!(line.price_at(&candles, 9 * DAY), None);
+    line.extend_right = true;
+    assert_eq!(line.price_at(&candles, 9 * DAY), Some(109.0));
+
+    let alerts = [Alert::new(Level::Line(line), Condition::Cross)];
+    let mut candles = candles;
+    let mut monitor = Monitor::new();
+    monitor.check(&alerts, &candles, None);
+
+    // The line is at 110 on day 10.
+    candles.insert(0, candle(10, 100.0, 109.0, 109.0));
+    assert!(monitor.check(&alerts, &candles, None).is_empty());
+    candles[0] = candle(10, 100.0, 111.0, 110.5);
+    assert_eq!(monitor.check(&alerts, &candles, None).len(), 1);
+}
+
+#[test]
+fn drawing_alerts_follow_their_drawing() {
+    let line = |price| Drawing::HorizontalLine(Anchor::new(0, price));
+    let mut drawings = Drawings::from(vec![line(105.0), line(120.0)]);
+    let alert = Alert::on(&drawings, 1, Condition::CrossUp).unwrap();
+    let alerts = [alert.clone()];
+    let mut candles = history();
+    let mut monitor = Monitor::new();
+    monitor.check(&alerts, &candles, Some(&drawings));
+
+    // Removing the drawing before it keeps the alert on its line, which
+    // now sits at 102.
+    drawings.perform(Action::Remove(0));
+    drawings.perform(Action::Replace(0, line(102.0)));
+    candles.insert(0, candle(10, 100.0, 103.0, 103.0));
+    let triggers = monitor.check(&alerts, &candles, Some(&drawings));
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].alert, alert.id());
+
+    // Without the line, crossing 102 again no longer triggers.
+    candles.insert(0, candle(11, 100.0, 101.0, 101.0));
+    assert!(monitor.check(&alerts, &candles, Some(&drawings)).is_empty());
+    drawings.perform(Action::Remove(0));
+    candles.insert(0, candle(12, 100.0, 104.0, 104.0));
+    assert!(monitor.check(&alerts, &candles, Some(&drawings)).is_empty());
+}
+
+#[test]
+fn triggers_stay_with_reordered_alerts() {
+    let up = Alert::new(Level::Price(105.0), Condition::CrossUp);
+    let touch = Alert::new(Level::Price(103.0), Condition::Touch);
+    let mut alerts = vec![up.clone(), touch.clone()];
+    let mut candles = history();
+    let mut monitor = Monitor::new();
+    monitor.check(&alerts, &candles, None);
+
+    candles.insert(0, candle(10, 100.0, 104.0, 104.0));
+    let triggers = monitor.check(&alerts, &candles, None);
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].alert, touch.id());
+
+    // Removing the first alert neither fires nor silences the other.
+    alerts.remove(0);
+    candles[0] = candle(10, 100.0, 104.5, 104.5);
+    assert!(monitor.check(&alerts, &candles, None).is_empty());
+    assert!(monitor.is_triggered(&touch));
+    assert!(!monitor.is_triggered(&up));
+}
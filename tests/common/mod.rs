@@ -0,0 +1,44 @@
+//! Candle fixtures shared by the integration tests.
+
+#![allow(dead_code)]
+
+use iced_charts::{candle::Candle, time::DAY};
+
+/// Daily candles from `[open, high, low, close]` prices given newest first,
+/// the oldest opening on day 0.
+pub fn daily(prices: impl IntoIterator<Item = [f32; 4]>) -> Vec<Candle> {
+    let prices: Vec<_> = prices.into_iter().collect();
+    let oldest = prices.len() as i64 - 1;
+
+    prices
+        .into_iter()
+        .enumerate()
+        .map(|(index, [open, high, low, close])| Candle {
+            time: (oldest - index as i64) * DAY,
+            open,
+            high,
+            low,
+            close,
+            volume: 0.0,
+        })
+        .collect()
+}
+
+/// `days` daily candles, newest first, priced by `prices` from their day.
+pub fn days(days: i64, prices: impl Fn(f32) -> [f32; 4]) -> Vec<Candle> {
+    daily((0..days).rev().map(|day| prices(day as f32)))
+}
+
+/// `days` daily candles opening `step` higher each day from `first`, trading
+/// `spread` either side of the open and closing halfway to the next open.
+pub fn rising(days: i64, first: f32, step: f32, spread: f32) -> Vec<Candle> {
+    self::days(days, |day| {
+        let open = first + day * step;
+        [open, open + spread, open - spread, open + step / 2.0]
+    })
+}
+
+/// `days` daily candles opening at 117000 and closing at 118000.
+pub fn flat(days: i64) -> Vec<Candle> {
+    self::days(days, |_| [117000.0, 120000.0, 114000.0, 118000.0])
+}
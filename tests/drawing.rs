@@ -0,0 +1,201 @@
+use iced::{Point, Rectangle, Size};
+use iced_charts::{
+    drawing::{
+        Action, Anchor, Annotation, AnnotationKind, Drawing, Drawings, Fibonacci, FibonacciKind,
+        Handle, Level, LevelLabels, Range, Tool, TrendLine,
+    },
+    time,
+    viewport::{Projection, ViewportManager},
+};
+
+mod common;
+
+fn bounds() -> Rectangle {
+    Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0))
+}
+
+#[test]
+fn anchors_follow_the_viewport() {
+    let candles = common::rising(50, 110000.0, 100.0, 2000.0);
+    let mut viewport = ViewportManager::new();
+    let anchor = Anchor::new(40 * time::DAY, 117420.0);
+
+    let before = Projection::new(&viewport, &candles, 10.0, bounds());
+    let position = anchor.position(&before).unwrap();
+    assert_eq!(Anchor::at(&before, position), Some(anchor));
+
+    viewport.offset.x += 50.0;
+    let after = Projection::new(&viewport, &candles, 10.0, bounds());
+    let panned = anchor.position(&after).unwrap();
+
+    assert!((panned.x - position.x - 50.0).abs() < 1e-3);
+    assert!((panned.y - position.y).abs() < 1e-3);
+}
+
+#[test]
+fn rays_extend_the_hit_area() {
+    let candles = common::rising(50, 110000.0, 100.0, 2000.0);
+    let viewport = ViewportManager::new();
+    let projection = Projection::new(&viewport, &candles, 10.0, bounds());
+
+    let mut line = TrendLine::new(
+        Anchor::new(40 * time::DAY, 110000.0),
+        Anchor::new(45 * time::DAY, 120000.0),
+    );
+    let end = line.end.position(&projection).unwrap();
+    let start = line.start.position(&projection).unwrap();
+    let beyond = end + (end - start);
+
+    assert_eq!(
+        Drawing::TrendLine(line).hit(&projection, end),
+        Some(Handle::Anchor(1))
+    );
+    assert_eq!(Drawing::TrendLine(line).hit(&projection, beyond), None);
+
+    line.extend_right = true;
+    assert_eq!(
+        Drawing::TrendLine(line).hit(&projection, beyond),
+        Some(Handle::Body)
+    );
+}
+
+#[test]
+fn actions_keep_the_selection_consistent() {
+    let line = |price| {
+        Drawing::TrendLine(TrendLine::new(
+            Anchor::new(0, price),
+            Anchor::new(time::DAY, price),
+        ))
+    };
+
+    let mut drawings = Drawings::new();
+    drawings.perform(Action::Add(line(1.0)));
+    drawings.perform(Action::Add(line(2.0)));
+    assert_eq!(drawings.selected(), Some(1));
+
+    drawings.perform(Action::Remove(0));
+    assert_eq!(drawings.selected(), Some(0));
+    assert_eq!(drawings.get(0), Some(&line(2.0)));
+
+    drawings.perform(Action::Select(Some(3)));
+    assert_eq!(drawings.selected(), None);
+
+    drawings.perform(Action::Replace(0, line(3.0)));
+    drawings.perform(Action::Remove(0));
+    assert!(drawings.is_empty());
+}
+
+#[test]
+fn magnet_snaps_to_the_nearest_price() {
+    let candles = common::rising(50, 110000.0, 100.0, 2000.0);
+    let viewport = ViewportManager::new();
+    let projection = Projection::new(&viewport, &candles, 10.0, bounds());
+
+    let candle = candles[10];
+    let high = Anchor::new(candle.time, candle.high)
+        .position(&projection)
+        .unwrap();
+    let near = high + iced::Vector::new(3.0, 4.0);
+
+    assert_eq!(
+        Anchor::snapped(&projection, near),
+        Some(Anchor::new(candle.time, candle.high))
+    );
+}
+
+#[test]
+fn levels_and_ranges_are_hit_across_the_chart() {
+    let candles = common::rising(50, 110000.0, 100.0, 2000.0);
+    let viewport = ViewportManager::new();
+    let projection = Projection::new(&viewport, &candles, 10.0, bounds());
+
+    let anchor = Anchor::new(45 * time::DAY, 117420.0);
+    let position = anchor.position(&projection).unwrap();
+
+    let level = Tool::HorizontalLine.create(anchor).unwrap();
+    assert_eq!(level.price_labels(), vec![(117420.0, None)]);
+    assert_eq!(
+        level.hit(&projection, Point::new(10.0, position.y + 2.0)),
+        Some(Handle::Body)
+    );
+
+    let dates = Drawing::DateRange(Range::new(
+        Anchor::new(40 * time::DAY, 110000.0),
+        Anchor::new(45 * time::DAY, 111000.0),
+    ));
+    assert_eq!(
+        dates.hit(&projection, Point::new(position.x - 10.0, 5.0)),
+        Some(Handle::Body)
+    );
+    assert_eq!(
+        dates.hit(&projection, Point::new(position.x + 20.0, 5.0)),
+        None
+    );
+}
+
+#[test]
+fn fibonacci_levels() {
+    let candles = common::rising(50, 110000.0, 100.0, 2000.0);
+    let start = Anchor::new(40 * time::DAY, 100.0);
+    let end = Anchor::new(38 * time::DAY, 200.0);
+
+    let mut retracement = Fibonacci::new(FibonacciKind::Retracement, start, end);
+    retracement.levels = vec![Level::new(0.5), Level::new(1.0)];
+    let prices: Vec<_> = retracement
+        .level_prices()
+        .iter()
+        .map(|(_, price)| *price)
+        .collect();
+    assert_eq!(prices, vec![150.0, 100.0]);
+
+    let extension = Fibonacci::new(FibonacciKind::Extension, start, end);
+    assert!(
+        extension
+            .level_prices()
+            .iter()
+            .any(|(_, price)| *price == 200.0)
+    );
+
+    let mut zones = Fibonacci::new(FibonacciKind::TimeZone, start, end);
+    zones.levels = vec![Level::new(1.0), Level::new(3.0)];
+    zones.labels = LevelLabels::Ratio;
+    let times: Vec<_> = zones
+        .level_times(&candles)
+        .iter()
+        .map(|(_, time)| *time)
+        .collect();
+    assert_eq!(times, vec![38 * time::DAY, 34 * time::DAY]);
+    assert_eq!(zones.label(&zones.levels[1], None).as_deref(), Some("3"));
+    assert!(zones.level_prices().is_empty());
+}
+
+#[test]
+fn annotations_stay_pinned_to_their_candle() {
+    let candles = common::rising(50, 110000.0, 100.0, 2000.0);
+    let mut viewport = ViewportManager::new();
+    let arrow = Annotation::new(
+        AnnotationKind::Arrow,
+        Anchor::new(42 * time::DAY, 117420.0),
+        "Breakout",
+    );
+
+    let projection = Projection::new(&viewport, &candles, 10.0, bounds());
+    let text = arrow.text_bounds(&projection).unwrap();
+    let tail = arrow.tail(&projection).unwrap();
+    let drawing = Drawing::Annotation(arrow.clone());
+
+    assert!(text.y + text.height < tail.y);
+    assert_eq!(
+        drawing.hit(&projection, tail + iced::Vector::new(2.0, 10.0)),
+        Some(Handle::Body)
+    );
+
+    viewport.offset.x -= 100.0;
+    let panned = Projection::new(&viewport, &candles, 10.0, bounds());
+    let moved = arrow.text_bounds(&panned).unwrap();
+
+    assert!((moved.x - text.x + 100.0).abs() < 1e-3);
+    assert_eq!(moved.y, text.y);
+    assert_eq!(drawing.hit(&panned, text.center()), None);
+    assert_eq!(drawing.hit(&panned, moved.center()), Some(Handle::Body));
+}
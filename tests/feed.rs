@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use iced_charts::{
+    aggregate::Bars,
+    candle::Candle,
+    feed::{self, LiveFeed, RandomWalk, ReplayFeed},
+    time::{self, Interval},
+};
+
+#[tokio::test]
+async fn replay_feed_streams_aggregated_ticks() {
+    let path = std::env::temp_dir().join("iced-charts-replay.csv");
+    tokio::fs::write(&path, "time,price,size\n0,10,1\n30,12,1\n61,11,2\n")
+        .await
+        .unwrap();
+
+    let updates: Vec<_> = ReplayFeed::new(&path, Bars::Time(Interval::Minutes(1)))
+        .speed(f32::INFINITY)
+        .updates()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+
+    let mut candles = Vec::new();
+    for update in updates {
+        feed::apply(&mut candles, update);
+    }
+
+    assert_eq!(candles.len(), 2);
+    assert_eq!((candles[1].open, candles[1].close), (10.0, 12.0));
+    assert_eq!(candles[0].volume, 2.0);
+}
+
+#[test]
+fn apply_replaces_or_inserts_by_time() {
+    let candle = |time, close| Candle {
+        time,
+        close,
+        ..Candle::default()
+    };
+    let mut candles = vec![candle(2, 2.0), candle(1, 1.0)];
+
+    feed::apply(&mut candles, candle(2, 5.0));
+    feed::apply(&mut candles, candle(3, 3.0));
+
+    assert_eq!(candles, [candle(3, 3.0), candle(2, 5.0), candle(1, 1.0)]);
+}
+
+#[tokio::test]
+async fn random_walks_open_bars_on_calendar_boundaries() {
+    let january = time::days_from_civil(2024, 1, 1) * time::DAY;
+    let last = Candle {
+        time: january,
+        close: 100.0,
+        ..Candle::default()
+    };
+
+    let times: Vec<_> = RandomWalk::new(last, Interval::Months(1))
+        .tick(Duration::ZERO)
+        .ticks_per_bar(4)
+        .updates()
+        .take(20)
+        .map(|update| update.unwrap().time)
+        .collect()
+        .await;
+
+    // Four ticks in each month from February to June, however long.
+    for (month, bar) in (2..).zip(times.chunks(4)) {
+        assert_eq!(bar, [time::days_from_civil(2024, month, 1) * time::DAY; 4]);
+    }
+}
@@ -0,0 +1,59 @@
+use iced::Color;
+use iced_charts::{
+    drawing::{Action, Anchor, Drawing},
+    history::{History, Step},
+    layout::ChartLayout,
+    style::StyleOverrides,
+};
+
+fn level(price: f32) -> Drawing {
+    Drawing::HorizontalLine(Anchor::new(0, price))
+}
+
+#[test]
+fn drawing_edits_undo_and_redo() {
+    let mut layout = ChartLayout::new();
+    let mut history = History::new();
+
+    history.perform(&mut layout, Action::Add(level(1.0)));
+    history.perform(&mut layout, Action::Add(level(2.0)));
+    history.perform(&mut layout, Action::Replace(0, level(3.0)));
+    history.perform(&mut layout, Action::Select(Some(1)));
+    history.perform(&mut layout, Action::Remove(0));
+    assert_eq!(layout.drawings.len(), 1);
+
+    assert!(history.undo(&mut layout));
+    assert_eq!(layout.drawings.get(0), Some(&level(3.0)));
+    assert_eq!(layout.drawings.selected(), Some(1));
+
+    assert!(history.undo(&mut layout));
+    assert_eq!(layout.drawings.get(0), Some(&level(1.0)));
+
+    assert!(history.step(&mut layout, Step::Redo));
+    assert_eq!(layout.drawings.get(0), Some(&level(3.0)));
+
+    while history.undo(&mut layout) {}
+    assert!(layout.drawings.is_empty());
+    assert!(!history.can_undo());
+    assert!(history.can_redo());
+}
+
+#[test]
+fn new_edits_clear_the_redo_stack() {
+    let mut layout = ChartLayout::new();
+    let mut history = History::new();
+    let red = StyleOverrides {
+        bearish: Some(Color::from_rgb(1.0, 0.0, 0.0)),
+        ..StyleOverrides::default()
+    };
+
+    history.set_style(&mut layout, red);
+    history.set_style(&mut layout, red);
+    assert!(history.undo(&mut layout));
+    assert_eq!(layout.style, StyleOverrides::default());
+    assert!(!history.can_undo());
+
+    history.perform(&mut layout, Action::Add(level(1.0)));
+    assert!(!history.redo(&mut layout));
+    assert_eq!(layout.style, StyleOverrides::default());
+}
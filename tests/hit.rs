@@ -0,0 +1,80 @@
+use iced::{Point, Rectangle, Size};
+use iced_charts::{
+    drawing::{Action, Anchor, Drawing, Drawings},
+    hit::{CandleHit, Target},
+    marker::{self, Marker},
+    order::OrderLine,
+    replay::Replay,
+    time::DAY,
+    viewport::{Projection, ViewportManager},
+};
+
+mod common;
+
+#[test]
+fn hits_report_the_candle_time_and_price() {
+    let candles = common::flat(30);
+    let viewport = ViewportManager::new();
+    let bounds = Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0));
+    let projection = Projection::new(&viewport, &candles, 10.0, bounds);
+    let at = |index: f32, price: f32| viewport.transform(index * 10.0, price, &bounds);
+
+    let hit = CandleHit::at(&projection, at(20.0, 117000.0), None, &[], &[]);
+    assert_eq!(hit.index, Some(20));
+    assert_eq!(hit.time, Some(9 * DAY));
+    assert!((hit.price - 117000.0).abs() < 1.0);
+    assert_eq!(hit.target, Target::Candle);
+
+    let hit = CandleHit::at(&projection, at(20.0, 130000.0), None, &[], &[]);
+    assert_eq!(hit.target, Target::Background);
+
+    let hit = CandleHit::at(&projection, at(32.0, 117000.0), None, &[], &[]);
+    assert_eq!(hit.index, None);
+    assert_eq!(hit.time, Some(-3 * DAY));
+
+    let hit = CandleHit::at(&projection, Point::new(790.0, 300.0), None, &[], &[]);
+    assert_eq!(hit.target, Target::PriceScale);
+    let hit = CandleHit::at(&projection, Point::new(400.0, 590.0), None, &[], &[]);
+    assert_eq!(hit.target, Target::TimeScale);
+}
+
+#[test]
+fn hits_prefer_elements_drawn_over_the_candles() {
+    let candles = common::flat(30);
+    let viewport = ViewportManager::new();
+    let bounds = Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0));
+    let projection = Projection::new(&viewport, &candles, 10.0, bounds);
+    let at = |index: f32, price: f32| viewport.transform(index * 10.0, price, &bounds);
+
+    let mut drawings = Drawings::new();
+    drawings.perform(Action::Add(Drawing::HorizontalLine(Anchor::new(
+        0, 115000.0,
+    ))));
+    let markers = [Marker::buy(5 * DAY, 114000.0)];
+    let orders = [OrderLine::position(119000.0, 1.0)];
+    let hit = |point| CandleHit::at(&projection, point, Some(&drawings), &markers, &orders);
+
+    assert_eq!(hit(at(20.0, 115000.0)).target, Target::Drawing(0));
+    assert_eq!(hit(at(20.0, 119000.0)).target, Target::Order(0));
+
+    let below = at(24.0, 114000.0) + iced::Vector::new(0.0, 4.0 + marker::SIZE / 2.0);
+    assert_eq!(hit(below).target, Target::Marker(0));
+}
+
+#[test]
+fn replayed_hits_index_the_whole_series() {
+    let candles = common::flat(30);
+    let replay = Replay::starting_at(5);
+    let visible = replay.visible(&candles);
+    let viewport = ViewportManager::new();
+    let bounds = Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0));
+    let projection = Projection::new(&viewport, visible, 10.0, bounds);
+    let position = viewport.transform(15.0 * 10.0, 117000.0, &bounds);
+
+    let hit = CandleHit::at(&projection, position, None, &[], &[]);
+    assert_eq!(hit.index, Some(15));
+
+    let index = replay.series_index(hit.index.unwrap());
+    assert_eq!(index, 20);
+    assert_eq!(Some(candles[index].time), hit.time);
+}
@@ -0,0 +1,92 @@
+use iced::Color;
+use iced_charts::{
+    drawing::{
+        Action, Anchor, Annotation, AnnotationKind, Drawing, Drawings, Fibonacci, FibonacciKind,
+        Range, TrendLine,
+    },
+    layout::ChartLayout,
+    style::{self, StyleOverrides},
+    time::{DAY, Interval},
+    viewport::ViewportManager,
+};
+
+fn layout() -> ChartLayout {
+    let mut drawings = Drawings::new();
+    let start = Anchor::new(DAY, 100.0);
+    let end = Anchor::new(5 * DAY, 120.5);
+
+    let mut line = TrendLine::new(start, end);
+    line.extend_right = true;
+    let mut fibonacci = Fibonacci::new(FibonacciKind::Retracement, start, end);
+    fibonacci.levels[2].color = Some(Color::from_rgb8(255, 128, 0));
+    let mut note = Annotation::new(AnnotationKind::Callout, end, "Earnings");
+    note.font_size = 18.0;
+
+    for drawing in [
+        Drawing::TrendLine(line),
+        Drawing::HorizontalLine(start),
+        Drawing::DateRange(Range::new(start, end)),
+        Drawing::Fibonacci(fibonacci),
+        Drawing::Annotation(note),
+    ] {
+        drawings.perform(Action::Add(drawing));
+    }
+    drawings.perform(Action::Select(None));
+
+    ChartLayout {
+        interval: Some(Interval::Weeks(1)),
+        viewport: Some(ViewportManager::new().viewport()),
+        style: StyleOverrides {
+            bullish: Some(Color::from_rgb8(0, 200, 100)),
+            candle_spacing: Some(12.0),
+            ..StyleOverrides::default()
+        },
+        drawings,
+    }
+}
+
+#[test]
+fn overrides_replace_only_what_is_set() {
+    let theme = style::default(&iced::Theme::Dark);
+    let style = layout().style.apply(theme);
+
+    assert_eq!(style.bullish, Color::from_rgb8(0, 200, 100));
+    assert_eq!(style.candle_spacing, 12.0);
+    assert_eq!(style.bearish, theme.bearish);
+    assert_eq!(style.candle_width, theme.candle_width);
+}
+
+#[test]
+fn viewport_restores() {
+    let saved = ViewportManager::new().viewport();
+    let mut viewport = ViewportManager::default();
+
+    viewport.restore(saved);
+    assert_eq!(viewport.viewport(), saved);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn layout_json_round_trip() {
+    let layout = layout();
+    let json = layout.to_json().unwrap();
+
+    assert!(json.contains(r#""type": "trend_line""#));
+    assert!(json.contains(r##""bullish": "#00c864ff""##));
+    assert_eq!(ChartLayout::from_json(&json).unwrap(), layout);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn partial_layouts_load() {
+    let layout = ChartLayout::from_json(
+        r#"{ "drawings": [{ "type": "vertical_line", "time": 0, "price": 1.0 }] }"#,
+    )
+    .unwrap();
+
+    assert_eq!(layout.drawings.len(), 1);
+    assert_eq!(layout.viewport, None);
+    assert_eq!(layout.style, StyleOverrides::default());
+
+    assert!(ChartLayout::from_json(r#"{ "style": { "background": "nope" } }"#).is_err());
+}
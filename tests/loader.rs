@@ -0,0 +1,145 @@
+use iced_charts::{
+    loader::{
+        RowErrorKind, TimestampFormat,
+        csv::{Column, Columns, CsvLoader, Header},
+        json::JsonLoader,
+    },
+    time,
+};
+
+#[test]
+fn parses_iso8601() {
+    assert_eq!(time::parse_iso8601("1970-01-02"), Some(time::DAY));
+    assert_eq!(
+        time::parse_iso8601("2024-02-29T12:30:00Z"),
+        Some(1_709_209_800_000)
+    );
+    assert_eq!(
+        time::parse_iso8601("2024-02-29 14:30:00.5+02:00"),
+        Some(1_709_209_800_500)
+    );
+    assert_eq!(time::parse_iso8601("2024-13-01"), None);
+    assert_eq!(time::civil_from_days(19_782), (2024, 2, 29));
+}
+
+#[test]
+fn csv_with_header_is_newest_first() {
+    let input = "\
+Date,Open,High,Low,Close,Volume
+2024-01-01,10,12,9,11,100
+2024-01-02,11,13,10,12.5,150
+";
+
+    let loaded = CsvLoader::new().parse(input).unwrap();
+
+    assert!(loaded.errors.is_empty());
+    assert_eq!(loaded.candles.len(), 2);
+    assert_eq!(loaded.candles[0].close, 12.5);
+    assert_eq!(loaded.candles[0].volume, 150.0);
+    assert!(loaded.candles[0].time > loaded.candles[1].time);
+}
+
+#[test]
+fn tsv_with_unix_seconds_and_custom_columns() {
+    let input = "1700000000\t1\t5\t0.5\t2\n1700000060\t2\t6\t1\t3\n";
+
+    let loaded = CsvLoader::tsv()
+        .header(Header::Absent)
+        .timestamp(TimestampFormat::UnixSeconds)
+        .columns(Columns {
+            time: Column::Index(0),
+            open: Column::Index(1),
+            high: Column::Index(2),
+            low: Column::Index(3),
+            close: Column::Index(4),
+            volume: None,
+        })
+        .parse(input)
+        .unwrap();
+
+    assert_eq!(loaded.candles.len(), 2);
+    assert_eq!(loaded.candles[0].time, 1_700_000_060_000);
+    assert_eq!(loaded.candles[1].open, 1.0);
+}
+
+#[test]
+fn reports_row_errors() {
+    let input = "\
+time;open;high;low;close
+1700000000000;1;2;0.5;1.5
+1700000060000;x;2;0.5;1.5
+yesterday;1;2;0.5;1.5
+1700000180000;1;2
+1700000240000;1;2;3;1.5
+";
+
+    let loaded = CsvLoader::new().parse(input).unwrap();
+
+    assert_eq!(loaded.candles.len(), 1);
+    let errors: Vec<_> = loaded.errors.iter().map(|e| (e.line, &e.kind)).collect();
+    assert_eq!(
+        errors,
+        [
+            (
+                3,
+                &RowErrorKind::InvalidNumber {
+                    field: "open",
+                    value: "x".to_owned()
+                }
+            ),
+            (4, &RowErrorKind::InvalidTimestamp("yesterday".to_owned())),
+            (5, &RowErrorKind::MissingField("low")),
+            (6, &RowErrorKind::InconsistentPrices),
+        ]
+    );
+}
+
+#[test]
+fn json_array_klines_with_string_decimals() {
+    let input = r#"[
+        [1700000000000, "1.50", "2.25", "1.00", "2.00", "1234.5", 1700000059999, "0", 10],
+        [1700000060000, "2.00", "2.50", "1.75", "1.80", "99"]
+    ]"#;
+
+    let loaded = JsonLoader::new().parse(input).unwrap();
+
+    assert!(loaded.errors.is_empty());
+    assert_eq!(loaded.candles[0].time, 1_700_000_060_000);
+    assert_eq!(loaded.candles[1].open, 1.5);
+    assert_eq!(loaded.candles[1].volume, 1234.5);
+}
+
+#[test]
+fn json_wrapped_objects() {
+    let input = r#"{"result": {"list": [
+        {"t": 1700000000, "o": 1, "h": "3", "l": 0.5, "c": 2, "v": 7},
+        {"time": "2024-01-01T00:00:00Z", "open": 1, "high": 2, "low": 0.5}
+    ]}}"#;
+
+    let loaded = JsonLoader::new().parse(input).unwrap();
+
+    assert_eq!(loaded.candles.len(), 1);
+    assert_eq!(loaded.candles[0].time, 1_700_000_000_000);
+    assert_eq!(loaded.candles[0].high, 3.0);
+    assert_eq!(loaded.errors[0].line, 2);
+    assert_eq!(loaded.errors[0].kind, RowErrorKind::MissingField("close"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn candle_serde_round_trip() {
+    let candle = iced_charts::candle::Candle {
+        time: 1_700_000_000_000,
+        open: 1.0,
+        high: 2.0,
+        low: 0.5,
+        close: 1.5,
+        volume: 10.0,
+    };
+
+    let json = serde_json::to_string(&candle).unwrap();
+    assert_eq!(
+        serde_json::from_str::<iced_charts::candle::Candle>(&json).unwrap(),
+        candle
+    );
+}
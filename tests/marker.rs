@@ -0,0 +1,52 @@
+use iced::{Point, Rectangle, Size};
+use iced_charts::{
+    marker::{self, Marker},
+    time::{DAY, HOUR},
+    viewport::{Projection, ViewportManager},
+};
+
+mod common;
+
+#[test]
+fn markers_land_on_the_bar_containing_their_time() {
+    let candles = common::flat(10);
+
+    assert_eq!(marker::bar(&candles, 3 * DAY), Some(6));
+    assert_eq!(marker::bar(&candles, 3 * DAY + 5 * HOUR), Some(6));
+    assert_eq!(marker::bar(&candles, 9 * DAY + 23 * HOUR), Some(0));
+    assert_eq!(marker::bar(&candles, 10 * DAY), None);
+    assert_eq!(marker::bar(&candles, 20 * DAY), None);
+    assert_eq!(marker::bar(&candles, -DAY), None);
+    assert_eq!(marker::bar(&[], 0), None);
+}
+
+#[test]
+fn shared_bars_stack_away_from_the_candle() {
+    let candles = common::flat(10);
+    let viewport = ViewportManager::new();
+    let bounds = Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0));
+    let projection = Projection::new(&viewport, &candles, 10.0, bounds);
+
+    let markers = [
+        Marker::buy(DAY, 115000.0),
+        Marker::buy(DAY + HOUR, 115500.0).text("Add"),
+        Marker::sell(DAY + 2 * HOUR, 119000.0),
+    ];
+    let placements = marker::place(&markers, &projection);
+    let low = viewport.transform(8.0 * 10.0, 114000.0, &bounds);
+    let high = viewport.transform(8.0 * 10.0, 120000.0, &bounds);
+
+    assert_eq!(placements.len(), 3);
+    assert!(
+        placements
+            .iter()
+            .all(|placement| placement.center.x == low.x)
+    );
+    assert!(placements[0].center.y > low.y);
+    assert!(placements[1].center.y >= placements[0].center.y + marker::SIZE);
+    assert!(placements[2].center.y < high.y);
+
+    assert_eq!(marker::hit(&placements, placements[1].center), Some(1));
+    assert_eq!(marker::hit(&placements, Point::new(0.0, 0.0)), None);
+    assert_eq!(markers[1].tooltip()[2], "Add");
+}
@@ -0,0 +1,44 @@
+use iced::{Point, Rectangle, Size};
+use iced_charts::{
+    candle::Candle,
+    menu::{self, Command, Item},
+    viewport::ViewportManager,
+};
+
+#[test]
+fn apps_extend_the_standard_items() {
+    let mut items = menu::standard();
+    items.push(Item::new("Add note", 42));
+
+    assert!(matches!(items[0].command, Command::ResetView));
+    assert!(matches!(items[3].command, Command::Alert));
+    assert!(matches!(items[4].command, Command::Message(42)));
+
+    let size = menu::size(items.iter().map(|item| item.label.as_str()));
+    assert_eq!(size.height, 5.0 * menu::ROW_HEIGHT);
+    assert!(size.width >= "Add horizontal line".len() as f32 * 7.0);
+}
+
+#[test]
+fn reset_view_centers_the_newest_close() {
+    let candles = [Candle {
+        time: 0,
+        open: 99.0,
+        high: 101.0,
+        low: 98.0,
+        close: 100.0,
+        volume: 0.0,
+    }];
+    let bounds = Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0));
+    let mut viewport = ViewportManager::new();
+    let default = viewport.viewport();
+    viewport.offset.x = 500.0;
+
+    viewport.reset(&candles);
+
+    assert_eq!(viewport.viewport().scale, default.scale);
+    assert_eq!(
+        viewport.transform(0.0, 100.0, &bounds),
+        Point::new(800.0, 300.0)
+    );
+}
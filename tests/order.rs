@@ -0,0 +1,60 @@
+use iced::{Point, Rectangle, Size};
+use iced_charts::{
+    candle::Candle,
+    order::{self, OrderLine},
+    viewport::{Projection, ViewportManager},
+};
+
+#[test]
+fn positions_label_their_profit_and_loss() {
+    let long = OrderLine::position(117000.0, 2.0);
+    let short = OrderLine::position(117000.0, -1.0).label("Short");
+
+    assert_eq!(long.pnl(117500.0), Some(1000.0));
+    assert_eq!(short.pnl(117500.0), Some(-500.0));
+    assert_eq!(OrderLine::limit(116000.0, 1.0).pnl(117500.0), None);
+
+    assert_eq!(
+        long.text(Some(117500.0)),
+        "Position 2 @ 117000.0  P&L +1000.00"
+    );
+    assert_eq!(short.text(None), "Short -1 @ 117000.0");
+    assert_eq!(
+        OrderLine::stop(116000.0, -2.0).text(Some(1.0)),
+        "Stop -2 @ 116000.0"
+    );
+}
+
+#[test]
+fn only_draggable_lines_are_grabbed() {
+    let candles = [Candle {
+        time: 0,
+        open: 117000.0,
+        high: 120000.0,
+        low: 114000.0,
+        close: 118000.0,
+        volume: 0.0,
+    }];
+    let viewport = ViewportManager::new();
+    let bounds = Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0));
+    let projection = Projection::new(&viewport, &candles, 10.0, bounds);
+
+    let lines = [
+        OrderLine::take_profit(119000.0, -1.0),
+        OrderLine::position(117000.0, 1.0),
+        OrderLine::stop(117000.0, -1.0).draggable(false),
+    ];
+    let at = |price: f32| viewport.transform(0.0, price, &bounds);
+
+    assert_eq!(order::hit(&lines, &projection, at(119000.0)), Some(0));
+    assert_eq!(
+        order::hit(
+            &lines,
+            &projection,
+            at(119000.0) + iced::Vector::new(0.0, 3.0)
+        ),
+        Some(0)
+    );
+    assert_eq!(order::hit(&lines, &projection, at(117000.0)), None);
+    assert_eq!(order::hit(&lines, &projection, at(118000.0)), None);
+}
@@ -0,0 +1,299 @@
+//! Golden-image tests for [`CandleRenderer`].
+//!
+//! Every case is rendered headlessly through [`SvgSurface`] and compared with
+//! the committed document in `tests/golden`, allowing coordinates to drift by
+//! [`TOLERANCE`] pixels. Run with `UPDATE_GOLDEN=1` to rewrite the goldens.
+
+use std::{fs, path::PathBuf};
+
+use iced::{Point, Rectangle, Size, mouse::Cursor};
+use iced_charts::{
+    alert::{Alert, Condition, Level, Monitor},
+    drawing::{Action, Anchor, Drawing, Drawings, Fibonacci, FibonacciKind},
+    marker::{Marker, Shape},
+    menu,
+    order::OrderLine,
+    renderer::CandleRenderer,
+    style::{self, StyleOverrides},
+    svg::{Export, SvgSurface},
+    time,
+    viewport::{Projection, ViewportManager},
+};
+
+mod common;
+
+const TOLERANCE: f32 = 0.5;
+const SIZE: Size = Size::new(400.0, 300.0);
+
+/// Open, high, low and close of five daily candles, newest first.
+const PRICES: [[f32; 4]; 5] = [
+    [117000.0, 130000.0, 110000.0, 125000.0],
+    [120000.0, 124000.0, 100000.0, 105000.0],
+    [100000.0, 118000.0, 95000.0, 117000.0],
+    [110000.0, 112000.0, 90000.0, 100000.0],
+    [95000.0, 111000.0, 92000.0, 110000.0],
+];
+
+fn render(draw: impl FnOnce(&mut SvgSurface, &ViewportManager, &Rectangle, &Rectangle)) -> String {
+    let viewport = ViewportManager::new();
+    let bounds = Rectangle::new(Point::ORIGIN, SIZE);
+    let window = viewport.get_window(&bounds);
+
+    let mut surface = SvgSurface::new(SIZE);
+    draw(&mut surface, &viewport, &window, &bounds);
+    surface.finish()
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Number(f32),
+    Text(&'a str),
+}
+
+fn tokenize(document: &str) -> Vec<Token<'_>> {
+    let bytes = document.as_bytes();
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let is_number = bytes[i].is_ascii_digit()
+            || (bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit));
+
+        if !is_number {
+            i += 1;
+            continue;
+        }
+
+        if start < i {
+            tokens.push(Token::Text(&document[start..i]));
+        }
+
+        let mut end = i + 1;
+        while end < bytes.len() && (bytes[end].is_ascii_digit() || bytes[end] == b'.') {
+            end += 1;
+        }
+
+        tokens.push(Token::Number(document[i..end].parse().unwrap()));
+        start = end;
+        i = end;
+    }
+
+    if start < bytes.len() {
+        tokens.push(Token::Text(&document[start..]));
+    }
+
+    tokens
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.svg"));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|error| panic!("missing golden {}: {error}", path.display()));
+
+    let expected_tokens = tokenize(&expected);
+    let actual_tokens = tokenize(actual);
+
+    assert_eq!(
+        expected_tokens.len(),
+        actual_tokens.len(),
+        "{name}: structure differs from golden\n{actual}"
+    );
+
+    for (expected, actual) in expected_tokens.iter().zip(&actual_tokens) {
+        match (expected, actual) {
+            (Token::Number(a), Token::Number(b)) if (a - b).abs() <= TOLERANCE => {}
+            (Token::Text(a), Token::Text(b)) if a == b => {}
+            _ => panic!("{name}: expected {expected:?}, found {actual:?}"),
+        }
+    }
+}
+
+#[test]
+fn candles_and_price_line() {
+    let style = style::default(&iced::Theme::Dark);
+    let candles = common::daily(PRICES);
+
+    let document = render(|surface, viewport, window, bounds| {
+        CandleRenderer::draw_chart(surface, viewport, &style, &candles, window, bounds);
+    });
+
+    assert_golden("candles", &document);
+}
+
+#[test]
+fn price_scale_ticks() {
+    let style = style::default(&iced::Theme::Dark);
+
+    let document = render(|surface, viewport, window, bounds| {
+        CandleRenderer::draw_overlay(
+            surface,
+            viewport,
+            &style,
+            &Cursor::Unavailable,
+            window,
+            bounds,
+            &[],
+        );
+    });
+
+    assert_golden("price_scale", &document);
+}
+
+#[test]
+fn crosshair_snaps_to_candle() {
+    let style = style::default(&iced::Theme::Dark);
+    let cursor = Cursor::Available(Point::new(153.0, 120.0));
+
+    let document = render(|surface, viewport, window, bounds| {
+        CandleRenderer::draw_overlay(surface, viewport, &style, &cursor, window, bounds, &[]);
+    });
+
+    assert_golden("crosshair", &document);
+}
+
+#[test]
+fn tokenizer_tolerates_small_drift() {
+    let a = tokenize("<line x1=\"10.20\" stroke=\"#4d4d4d\"/>");
+    let b = tokenize("<line x1=\"10.45\" stroke=\"#4d4d4d\"/>");
+
+    assert_eq!(a.len(), b.len());
+    assert_eq!(a[0], Token::Text("<line x"));
+}
+
+#[test]
+fn fibonacci_levels_on_the_price_scale() {
+    let style = style::default(&iced::Theme::Dark);
+    let candles = common::daily(PRICES);
+
+    let mut drawings = Drawings::new();
+    drawings.perform(Action::Add(Drawing::Fibonacci(Fibonacci::new(
+        FibonacciKind::Retracement,
+        Anchor::new(3 * time::DAY, 90000.0),
+        Anchor::new(0, 130000.0),
+    ))));
+
+    let document = render(|surface, viewport, window, bounds| {
+        let projection = Projection::new(viewport, &candles, style.candle_spacing, *bounds);
+        let marks = CandleRenderer::price_marks(&style, &drawings, None);
+
+        CandleRenderer::draw_drawings(surface, &projection, &style, &drawings, None);
+        CandleRenderer::draw_overlay(
+            surface,
+            viewport,
+            &style,
+            &Cursor::Unavailable,
+            window,
+            bounds,
+            &marks,
+        );
+    });
+
+    assert_golden("fibonacci", &document);
+}
+
+#[test]
+fn markers_stack_beside_their_candle() {
+    let style = style::default(&iced::Theme::Dark);
+    let candles = common::daily(PRICES);
+    let markers = [
+        Marker::buy(0, 117000.0).text("Long"),
+        Marker::buy(time::HOUR, 118000.0).shape(Shape::Circle),
+        Marker::sell(2 * time::DAY, 100000.0).shape(Shape::Flag),
+    ];
+
+    let document = render(|surface, viewport, _window, bounds| {
+        let projection = Projection::new(viewport, &candles, style.candle_spacing, *bounds);
+        CandleRenderer::draw_markers(surface, &projection, &style, &markers);
+    });
+
+    assert_golden("markers", &document);
+}
+
+#[test]
+fn order_lines_label_their_price() {
+    let style = style::default(&iced::Theme::Dark);
+    let candles = common::daily(PRICES);
+    let lines = [
+        OrderLine::position(100000.0, 0.5),
+        OrderLine::limit(98000.0, 1.0),
+        OrderLine::stop(95000.0, -0.5),
+        OrderLine::take_profit(110000.0, -0.5),
+    ];
+
+    let document = render(|surface, viewport, _window, bounds| {
+        let projection = Projection::new(viewport, &candles, style.candle_spacing, *bounds);
+        CandleRenderer::draw_order_lines(surface, &projection, &style, &lines, Some((1, 99000.0)));
+    });
+
+    assert_golden("orders", &document);
+}
+
+#[test]
+fn context_menu_highlights_the_hovered_item() {
+    let style = style::default(&iced::Theme::Dark);
+    let items = menu::standard::<()>();
+    let labels: Vec<_> = items.iter().map(|item| item.label.as_str()).collect();
+
+    let document = render(|surface, _viewport, _window, _bounds| {
+        CandleRenderer::draw_context_menu(surface, &style, &labels, Some(1));
+    });
+
+    assert_golden("context_menu", &document);
+}
+
+#[test]
+fn export_draws_what_the_chart_draws() {
+    let candles = common::daily(PRICES);
+    let viewport = ViewportManager::new();
+    let markers = [Marker::buy(time::DAY, 95000.0).text("Long")];
+    let orders = [OrderLine::limit(98000.0, 1.0)];
+    let alerts = [Alert::new(Level::Price(120000.0), Condition::Cross).name("High")];
+    let mut drawings = Drawings::new();
+    drawings.perform(Action::Add(Drawing::HorizontalLine(Anchor::new(
+        time::DAY,
+        105000.0,
+    ))));
+
+    let document = Export::new(&candles, &viewport, style::default(&iced::Theme::Dark))
+        .overrides(StyleOverrides {
+            drawing: Some(iced::Color::from_rgb(1.0, 0.0, 1.0)),
+            ..StyleOverrides::default()
+        })
+        .drawings(&drawings)
+        .markers(&markers)
+        .orders(&orders)
+        .alerts(&alerts)
+        .render(SIZE);
+
+    assert!(document.contains("#ff00ff"));
+    assert_golden("export", &document);
+}
+
+#[test]
+fn export_fills_triggered_alerts() {
+    let mut candles = common::daily(PRICES);
+    let viewport = ViewportManager::new();
+    let alerts = [Alert::new(Level::Price(120000.0), Condition::Cross)];
+
+    // The newest candle crosses the alert as it ticks from 115000.
+    let mut monitor = Monitor::new();
+    candles[0].close = 115000.0;
+    monitor.check(&alerts, &candles, None);
+    candles[0].close = 125000.0;
+    assert_eq!(monitor.check(&alerts, &candles, None).len(), 1);
+
+    let export =
+        || Export::new(&candles, &viewport, style::default(&iced::Theme::Dark)).alerts(&alerts);
+    let untriggered = export().render(SIZE);
+    assert_ne!(export().monitor(&monitor).render(SIZE), untriggered);
+    assert_eq!(export().monitor(&Monitor::new()).render(SIZE), untriggered);
+}
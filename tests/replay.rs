@@ -0,0 +1,55 @@
+use iced::Point;
+use iced_charts::{
+    candle::Candle,
+    replay::{Action, Control, Replay},
+};
+
+#[test]
+fn steps_through_history() {
+    let candles: Vec<_> = (0..5)
+        .rev()
+        .map(|time| Candle {
+            time,
+            ..Candle::default()
+        })
+        .collect();
+
+    let mut replay = Replay::new();
+    assert_eq!(replay.visible(&candles).len(), 5);
+
+    replay.perform(Action::Start(3), candles.len());
+    assert_eq!(replay.visible(&candles)[0].time, 1);
+
+    replay.perform(Action::Play, candles.len());
+    replay.perform(Action::Tick, candles.len());
+    replay.perform(Action::StepForward, candles.len());
+    assert_eq!(replay.cursor, Some(1));
+    assert!(replay.playing);
+
+    replay.perform(Action::Tick, candles.len());
+    assert_eq!(replay.visible(&candles).len(), 5);
+    assert!(!replay.playing);
+
+    replay.perform(Action::StepBack, candles.len());
+    assert_eq!(replay.visible(&candles)[0].time, 3);
+
+    for _ in 0..10 {
+        replay.perform(Action::StepBack, candles.len());
+    }
+    assert_eq!(replay.cursor, Some(4));
+    replay.perform(Action::StepForward, candles.len());
+    assert_eq!(replay.visible(&candles)[0].time, 1);
+}
+
+#[test]
+fn controls_map_to_actions() {
+    let mut replay = Replay::starting_at(2);
+
+    let play = Control::at(Control::PlayPause.bounds().center()).unwrap();
+    assert_eq!(play.action(&replay), Action::Play);
+
+    replay.perform(Action::Play, 5);
+    assert_eq!(play.action(&replay), Action::Pause);
+    assert_eq!(Control::Speed.action(&replay), Action::Speed(2.0));
+    assert_eq!(Control::at(Point::new(-10.0, -10.0)), None);
+}
@@ -0,0 +1,118 @@
+use iced_charts::{
+    candle::{self, Candle},
+    resample::{Session, resample},
+    time::{self, HOUR, Interval},
+};
+
+fn hourly(count: i64) -> Vec<Candle> {
+    (0..count)
+        .rev()
+        .map(|i| Candle {
+            time: i * HOUR,
+            open: i as f32,
+            high: i as f32 + 10.0,
+            low: i as f32 - 10.0,
+            close: i as f32 + 1.0,
+            volume: 1.0,
+        })
+        .collect()
+}
+
+#[test]
+fn merges_into_larger_buckets() {
+    let candles = resample(&hourly(10), Interval::Hours(4), Session::UTC);
+
+    assert_eq!(candles.len(), 3);
+    assert_eq!(candles[0].time, 8 * HOUR);
+    assert_eq!(
+        (
+            candles[1].open,
+            candles[1].high,
+            candles[1].low,
+            candles[1].close
+        ),
+        (4.0, 17.0, -6.0, 8.0)
+    );
+    assert_eq!(candles[1].volume, 4.0);
+    assert_eq!(candles[0].volume, 2.0);
+}
+
+#[test]
+fn daily_buckets_follow_the_session() {
+    // A 17:00 open in UTC-5 starts trading days at 22:00 UTC.
+    let session = Session::new(-5 * HOUR, -7 * HOUR);
+    let candles = resample(&hourly(48), Interval::Days(1), session);
+
+    assert_eq!(candles.len(), 3);
+    assert_eq!(candles[0].time, 46 * HOUR);
+    assert_eq!(candles[1].time, 22 * HOUR);
+    assert_eq!(candles[2].time, -2 * HOUR);
+    assert_eq!(candles[2].volume, 22.0);
+}
+
+/// New York, on daylight saving time from 10 March 2024 07:00 UTC.
+fn new_york(time: i64) -> i64 {
+    if time < time::days_from_civil(2024, 3, 10) * time::DAY + 7 * HOUR {
+        -5 * HOUR
+    } else {
+        -4 * HOUR
+    }
+}
+
+#[test]
+fn daily_buckets_follow_daylight_saving() {
+    let start = time::parse_iso8601("2024-03-08T00:00:00Z").unwrap();
+    let hours: Vec<_> = hourly(96)
+        .into_iter()
+        .map(|candle| Candle {
+            time: start + candle.time,
+            ..candle
+        })
+        .collect();
+    let candles = resample(
+        &hours,
+        Interval::Days(1),
+        Session::zoned(new_york, -7 * HOUR),
+    );
+
+    // The 17:00 open moves from 22:00 to 21:00 UTC.
+    let opens: Vec<_> = candles.iter().rev().map(|candle| candle.time).collect();
+    assert_eq!(
+        opens,
+        vec![
+            start - 2 * HOUR,
+            start + 22 * HOUR,
+            start + 46 * HOUR,
+            start + 69 * HOUR,
+            start + 93 * HOUR,
+        ]
+    );
+}
+
+#[test]
+fn time_and_index_round_trip() {
+    let candles = hourly(5);
+
+    assert_eq!(candle::time_at(&candles, 1.5), Some(2 * HOUR + HOUR / 2));
+    assert_eq!(candle::index_at(&candles, 2 * HOUR + HOUR / 2), Some(1.5));
+    assert_eq!(candle::index_at(&candles, 6 * HOUR), Some(-2.0));
+    assert_eq!(candle::time_at(&candles, 6.0), Some(-2 * HOUR));
+    assert_eq!(candle::index_at(&[], time::DAY), None);
+}
+
+#[test]
+fn formats_times() {
+    let time = time::parse_iso8601("2024-03-15T09:05:00Z").unwrap();
+
+    assert_eq!(time::format(time), "2024-03-15 09:05");
+    assert_eq!(
+        time::format(time::days_from_civil(1969, 12, 31) * time::DAY),
+        "1969-12-31"
+    );
+    assert_eq!(
+        time::format_duration(3 * time::DAY + 4 * HOUR + 5 * time::MINUTE),
+        "3d 4h"
+    );
+    assert_eq!(time::format_duration(-90 * time::SECOND), "1m 30s");
+    assert_eq!(time::format_duration(0), "0s");
+}
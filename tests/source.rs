@@ -0,0 +1,64 @@
+use iced_charts::{
+    candle::Candle,
+    source::{self, DataSource, FileSource, HistoryRequest, MemorySource},
+};
+
+fn candle(time: i64, close: f32) -> Candle {
+    Candle {
+        time,
+        open: close,
+        high: close,
+        low: close,
+        close,
+        volume: 0.0,
+    }
+}
+
+#[tokio::test]
+async fn pages_history_before_the_oldest_candle() {
+    let source = MemorySource::new((0..10).map(|i| candle(i, i as f32)).collect());
+
+    let latest = source
+        .history(HistoryRequest {
+            before: None,
+            limit: 3,
+        })
+        .await
+        .unwrap();
+    assert_eq!(latest.iter().map(|c| c.time).collect::<Vec<_>>(), [9, 8, 7]);
+
+    let older = source
+        .history(HistoryRequest {
+            before: Some(2),
+            limit: 5,
+        })
+        .await
+        .unwrap();
+    assert_eq!(older.iter().map(|c| c.time).collect::<Vec<_>>(), [1, 0]);
+}
+
+#[tokio::test]
+async fn file_source_reads_csv() {
+    let path = std::env::temp_dir().join("iced-charts-file-source.csv");
+    tokio::fs::write(&path, "time,open,high,low,close\n1,1,1,1,1\n2,2,2,2,2\n")
+        .await
+        .unwrap();
+
+    let page = FileSource::new(&path)
+        .history(HistoryRequest {
+            before: Some(2000),
+            limit: 10,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(page, [candle(1000, 1.0)]);
+}
+
+#[test]
+fn merge_replaces_overlapping_candles() {
+    let mut candles = vec![candle(3, 3.0), candle(2, 2.0)];
+    source::merge(&mut candles, vec![candle(2, 20.0), candle(1, 1.0)]);
+
+    assert_eq!(candles, [candle(3, 3.0), candle(2, 20.0), candle(1, 1.0)]);
+}
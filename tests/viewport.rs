@@ -0,0 +1,375 @@
+use iced::{
+    Event, Point, Rectangle, Size, keyboard,
+    mouse::{self, Cursor},
+    time::{Duration, Instant},
+};
+use iced_charts::{
+    bindings::{Bindings, WheelAction},
+    renderer::PRICE_SCALE_WIDTH,
+    time::DAY,
+    viewport::{Axis, ViewportManager},
+};
+
+mod common;
+
+fn bounds() -> Rectangle {
+    Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0))
+}
+
+/// Runs frames until the transition under way is over.
+fn settle(viewport: &mut ViewportManager) {
+    let start = Instant::now();
+    viewport.tick(start);
+    viewport.tick(start + Duration::from_secs(1));
+}
+
+#[test]
+fn zoom_to_range_fits_time_and_price() {
+    let candles = common::rising(50, 100.0, 1.0, 1.0);
+    let bounds = bounds();
+    let mut viewport = ViewportManager::new();
+
+    // Days 10 to 19 sit at indices 39 to 30 and trade from 109 to 120.
+    viewport.zoom_to_range(&candles, 10 * DAY, 19 * DAY, &bounds, 10.0);
+
+    // The newest candle ends at the price scale rather than under it.
+    let oldest = viewport.transform(39.5 * 10.0, 109.0, &bounds);
+    let newest = viewport.transform(29.5 * 10.0, 120.0, &bounds);
+    assert!(oldest.x.abs() < 0.5, "{oldest:?}");
+    assert!(
+        (newest.x - bounds.width + PRICE_SCALE_WIDTH).abs() < 0.5,
+        "{newest:?}"
+    );
+    assert!(oldest.y < bounds.height && oldest.y > bounds.height * 0.9);
+    assert!(newest.y > 0.0 && newest.y < bounds.height * 0.1);
+}
+
+#[test]
+fn shift_dragging_zooms_into_the_box() {
+    let bounds = bounds();
+    let mut viewport = ViewportManager::new();
+    let send = |viewport: &mut ViewportManager, event: Event, at: Point| {
+        viewport.on_box_zoom(&event, bounds, Cursor::Available(at), false)
+    };
+
+    let start = Point::new(200.0, 100.0);
+    let end = Point::new(400.0, 400.0);
+    let top = viewport.untransform(0.0, start.y, &bounds).y;
+    let bottom = viewport.untransform(0.0, end.y, &bounds).y;
+    let left = viewport.index_at(start.x, &bounds, 1.0);
+
+    let press = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+    assert_eq!(send(&mut viewport, press.clone(), start), (false, false));
+
+    viewport.on_event(
+        &Event::Keyboard(keyboard::Event::KeyPressed {
+            key: keyboard::Key::Named(keyboard::key::Named::Shift),
+            modified_key: keyboard::Key::Named(keyboard::key::Named::Shift),
+            physical_key: keyboard::key::Physical::Unidentified(
+                keyboard::key::NativeCode::Unidentified,
+            ),
+            location: keyboard::Location::Left,
+            modifiers: keyboard::Modifiers::SHIFT,
+            text: None,
+            repeat: false,
+        }),
+        bounds,
+        Cursor::Unavailable,
+    );
+    assert_eq!(send(&mut viewport, press, start), (true, false));
+    send(
+        &mut viewport,
+        Event::Mouse(mouse::Event::CursorMoved { position: end }),
+        end,
+    );
+    assert_eq!(
+        viewport.selection(),
+        Some(Rectangle::new(start, Size::new(200.0, 300.0)))
+    );
+
+    let release = Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left));
+    assert_eq!(send(&mut viewport, release, end), (true, true));
+    assert!(viewport.is_animating());
+    settle(&mut viewport);
+    assert_eq!(viewport.selection(), None);
+
+    let corner = viewport.transform(left, top, &bounds);
+    assert!(corner.x.abs() < 0.5 && corner.y.abs() < 0.5, "{corner:?}");
+    assert!((viewport.transform(left, bottom, &bounds).y - bounds.height).abs() < 0.5);
+}
+
+#[test]
+fn axes_scale_by_dragging_and_reset_by_double_clicking() {
+    let candles = common::rising(50, 100.0, 1.0, 1.0);
+    let bounds = bounds();
+    let mut viewport = ViewportManager::new();
+    let send = |viewport: &mut ViewportManager, event: mouse::Event, at: Point| {
+        viewport.on_axis_event(
+            &Event::Mouse(event),
+            bounds,
+            Cursor::Available(at),
+            &candles,
+            10.0,
+        )
+    };
+    let press = mouse::Event::ButtonPressed(mouse::Button::Left);
+    let release = mouse::Event::ButtonReleased(mouse::Button::Left);
+    let moved = |position| mouse::Event::CursorMoved { position };
+
+    let price_scale = Point::new(750.0, 300.0);
+    let time_scale = Point::new(400.0, 590.0);
+    assert_eq!(Axis::at(bounds.size(), price_scale), Some(Axis::Price));
+    assert_eq!(Axis::at(bounds.size(), time_scale), Some(Axis::Time));
+    assert_eq!(Axis::at(bounds.size(), Point::new(400.0, 300.0)), None);
+    assert_eq!(
+        send(&mut viewport, press, Point::new(400.0, 300.0)),
+        (false, false)
+    );
+
+    // Dragging the price scale up stretches the prices around the center.
+    let height = viewport.viewport().height;
+    assert_eq!(send(&mut viewport, press, price_scale), (true, false));
+    assert_eq!(viewport.axis_drag(), Some(Axis::Price));
+    let up = price_scale - iced::Vector::new(0.0, 100.0);
+    assert_eq!(send(&mut viewport, moved(up), up), (true, true));
+    assert!(viewport.viewport().height < height);
+    assert_eq!(send(&mut viewport, release, up), (true, false));
+    assert_eq!(viewport.axis_drag(), None);
+
+    // Dragging the time scale right spaces the candles out.
+    let right = time_scale + iced::Vector::new(100.0, 0.0);
+    send(&mut viewport, press, time_scale);
+    send(&mut viewport, moved(right), right);
+    send(&mut viewport, release, right);
+    assert!(viewport.viewport().scale > 1.0);
+
+    send(&mut viewport, press, right);
+    send(&mut viewport, release, right);
+    assert_eq!(send(&mut viewport, press, right), (true, true));
+    settle(&mut viewport);
+    assert_eq!(viewport.viewport().scale, 1.0);
+
+    // Double-clicking the price scale fits the visible candles, here all of
+    // them, trading from 99 to 150.
+    send(&mut viewport, release, right);
+    send(&mut viewport, press, price_scale);
+    send(&mut viewport, release, price_scale);
+    send(&mut viewport, press, price_scale);
+    settle(&mut viewport);
+    let top = viewport.transform(0.0, 150.0, &bounds).y;
+    let bottom = viewport.transform(0.0, 99.0, &bounds).y;
+    assert!(top > 0.0 && top < 40.0, "{top}");
+    assert!(
+        bottom < bounds.height && bottom > bounds.height - 40.0,
+        "{bottom}"
+    );
+}
+
+#[test]
+fn wheel_zoom_eases_in() {
+    let bounds = bounds();
+    let mut viewport = ViewportManager::new();
+    let wheel = Event::Mouse(mouse::Event::WheelScrolled {
+        delta: mouse::ScrollDelta::Lines { x: 0.0, y: 2.0 },
+    });
+
+    assert!(viewport.on_event(&wheel, bounds, Cursor::Available(Point::new(400.0, 300.0))));
+    assert_eq!(viewport.viewport().scale, 1.0);
+    assert_eq!(viewport.target().scale, 1.2);
+
+    let start = Instant::now();
+    viewport.tick(start);
+    viewport.tick(start + Duration::from_millis(100));
+    let halfway = viewport.viewport().scale;
+    assert!(halfway > 1.1 && halfway < 1.2, "{halfway}");
+
+    viewport.tick(start + Duration::from_millis(300));
+    assert_eq!(viewport.viewport().scale, 1.2);
+    assert!(!viewport.is_animating());
+
+    viewport.set_animated(false);
+    viewport.on_event(&wheel, bounds, Cursor::Available(Point::new(400.0, 300.0)));
+    assert!((viewport.viewport().scale - 1.4).abs() < 1e-5);
+}
+
+#[test]
+fn flings_coast_to_a_stop() {
+    let bounds = bounds();
+    let mut viewport = ViewportManager::new();
+    let start = Instant::now();
+    let frame = |n: u64| start + Duration::from_millis(n * 16);
+    let at = |x: f32| Cursor::Available(Point::new(x, 300.0));
+
+    viewport.on_event(
+        &Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+        bounds,
+        at(400.0),
+    );
+    viewport.tick(frame(0));
+    for n in 1..=3 {
+        let x = 400.0 + n as f32 * 20.0;
+        viewport.on_event(
+            &Event::Mouse(mouse::Event::CursorMoved {
+                position: Point::new(x, 300.0),
+            }),
+            bounds,
+            at(x),
+        );
+        viewport.tick(frame(n as u64));
+    }
+    // A last frame without movement slows the fling but does not stop it.
+    viewport.tick(frame(4));
+    assert!(viewport.is_animating());
+    viewport.on_event(
+        &Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+        bounds,
+        at(460.0),
+    );
+
+    let released = viewport.offset.x;
+    assert!(viewport.tick(frame(5)));
+    assert!(viewport.offset.x > released);
+
+    let mut n = 6;
+    while viewport.is_animating() {
+        viewport.tick(frame(n));
+        n += 1;
+        assert!(n < 1000, "coasting never stopped");
+    }
+    assert!(viewport.offset.x > released + 20.0);
+}
+
+#[test]
+fn bindings_rebind_the_wheel_and_pan_button() {
+    let bounds = bounds();
+    let cursor = Cursor::Available(Point::new(400.0, 300.0));
+    let wheel = |y: f32| {
+        Event::Mouse(mouse::Event::WheelScrolled {
+            delta: mouse::ScrollDelta::Lines { x: 0.0, y },
+        })
+    };
+    let mut viewport = ViewportManager::new();
+    viewport.set_animated(false);
+    viewport.set_bindings(Bindings {
+        wheel: WheelAction::Pan,
+        shift_wheel: WheelAction::Zoom,
+        invert_wheel: true,
+        pan_button: mouse::Button::Middle,
+        zoom_sensitivity: 0.5,
+        max_zoom: 2.0,
+        ..Bindings::default()
+    });
+
+    viewport.on_event(&wheel(1.0), bounds, cursor);
+    assert_eq!(viewport.viewport().scale, 1.0);
+    assert_eq!(viewport.offset.x, -50.0);
+
+    viewport.on_event(
+        &Event::Keyboard(keyboard::Event::KeyPressed {
+            key: keyboard::Key::Named(keyboard::key::Named::Shift),
+            modified_key: keyboard::Key::Named(keyboard::key::Named::Shift),
+            physical_key: keyboard::key::Physical::Unidentified(
+                keyboard::key::NativeCode::Unidentified,
+            ),
+            location: keyboard::Location::Left,
+            modifiers: keyboard::Modifiers::SHIFT,
+            text: None,
+            repeat: false,
+        }),
+        bounds,
+        cursor,
+    );
+    viewport.on_event(&wheel(-1.0), bounds, cursor);
+    assert_eq!(viewport.viewport().scale, 1.5);
+    viewport.on_event(&wheel(-4.0), bounds, cursor);
+    assert_eq!(viewport.viewport().scale, 2.0);
+
+    viewport.set_bindings(Bindings {
+        shift_wheel: WheelAction::ScalePrice,
+        price_sensitivity: 20000.0,
+        min_height: 30000.0,
+        ..*viewport.bindings()
+    });
+    viewport.on_event(&wheel(-1.0), bounds, cursor);
+    assert_eq!(viewport.viewport().height, 40000.0);
+    viewport.on_event(&wheel(-1.0), bounds, cursor);
+    assert_eq!(viewport.viewport().height, 30000.0);
+
+    let left = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+    let middle = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Middle));
+    assert!(!viewport.on_event(&left, bounds, cursor));
+    assert!(viewport.on_event(&middle, bounds, cursor));
+}
+
+#[test]
+fn keys_pan_and_zoom() {
+    let candles = common::rising(50, 100.0, 1.0, 1.0);
+    let bounds = bounds();
+    let mut viewport = ViewportManager::new();
+    viewport.set_animated(false);
+    let press = |key: keyboard::Key| {
+        Event::Keyboard(keyboard::Event::KeyPressed {
+            key: key.clone(),
+            modified_key: key,
+            physical_key: keyboard::key::Physical::Unidentified(
+                keyboard::key::NativeCode::Unidentified,
+            ),
+            location: keyboard::Location::Standard,
+            modifiers: keyboard::Modifiers::empty(),
+            text: None,
+            repeat: false,
+        })
+    };
+    let named = |key| press(keyboard::Key::Named(key));
+    let on_key = |viewport: &mut ViewportManager, event: Event| {
+        viewport.on_key(&event, &candles, &bounds, 10.0)
+    };
+
+    assert!(on_key(
+        &mut viewport,
+        named(keyboard::key::Named::ArrowLeft)
+    ));
+    assert_eq!(viewport.offset.x, 50.0);
+    on_key(&mut viewport, named(keyboard::key::Named::PageUp));
+    assert_eq!(viewport.offset.x, 750.0);
+    on_key(&mut viewport, named(keyboard::key::Named::End));
+    assert_eq!(viewport.offset.x, 0.0);
+
+    let price = viewport.offset.y;
+    on_key(&mut viewport, named(keyboard::key::Named::ArrowUp));
+    assert!(viewport.offset.y > price);
+
+    on_key(&mut viewport, press(keyboard::Key::Character("+".into())));
+    assert!((viewport.viewport().scale - 1.1).abs() < 1e-5);
+
+    // Zoom in until the candles no longer fit.
+    for _ in 0..9 {
+        on_key(&mut viewport, press(keyboard::Key::Character("=".into())));
+    }
+    on_key(&mut viewport, named(keyboard::key::Named::Home));
+    let oldest = viewport.index_at(0.0, &bounds, 10.0);
+    assert!((oldest - 49.0).abs() < 1e-3, "{oldest}");
+
+    assert!(!on_key(
+        &mut viewport,
+        press(keyboard::Key::Character("a".into()))
+    ));
+}
+
+#[test]
+fn reveal_pans_a_candle_into_view() {
+    let bounds = bounds();
+    let mut viewport = ViewportManager::new();
+
+    viewport.reveal(0, &bounds, 10.0);
+    let x = viewport.transform(0.0, 0.0, &bounds).x;
+    assert!(x <= bounds.width - 100.0, "{x}");
+
+    viewport.reveal(200, &bounds, 10.0);
+    let x = viewport.transform(2000.0, 0.0, &bounds).x;
+    assert!(x >= 0.0, "{x}");
+
+    let offset = viewport.offset;
+    viewport.reveal(195, &bounds, 10.0);
+    assert_eq!(viewport.offset, offset);
+}
@@ -0,0 +1,56 @@
+use iced::{
+    Renderer, Theme,
+    advanced::widget::{Tree, Widget},
+};
+use iced_charts::{
+    drawing::{Action, Anchor, Drawing, Drawings},
+    replay::Replay,
+    widget::{CandleChart, State},
+};
+
+mod common;
+
+type Chart<'a> = CandleChart<'a, (), Theme>;
+
+fn invalidated(tree: &Tree) -> u64 {
+    tree.state.downcast_ref::<State>().invalidated()
+}
+
+#[test]
+fn new_inputs_redraw_the_chart() {
+    let candles = common::flat(10);
+    let chart = Chart::new(candles.clone());
+    let mut tree = Tree::new(&chart as &dyn Widget<(), Theme, Renderer>);
+
+    // The same inputs keep the cached layers.
+    Chart::new(candles.clone()).diff(&mut tree);
+    assert_eq!(invalidated(&tree), 0);
+
+    // A tick on the newest candle, with no event in between.
+    let mut ticked = candles.clone();
+    ticked[0].close += 100.0;
+    Chart::new(ticked.clone()).diff(&mut tree);
+    assert_eq!(invalidated(&tree), 1);
+
+    Chart::new(common::flat(11)).diff(&mut tree);
+    assert_eq!(invalidated(&tree), 2);
+
+    Chart::new(common::flat(11))
+        .replay(Replay::starting_at(3))
+        .diff(&mut tree);
+    assert_eq!(invalidated(&tree), 3);
+
+    let mut drawings = Drawings::new();
+    Chart::new(common::flat(11))
+        .drawings(&drawings)
+        .diff(&mut tree);
+    assert_eq!(invalidated(&tree), 4);
+
+    drawings.perform(Action::Add(Drawing::HorizontalLine(Anchor::new(
+        0, 118000.0,
+    ))));
+    Chart::new(common::flat(11))
+        .drawings(&drawings)
+        .diff(&mut tree);
+    assert_eq!(invalidated(&tree), 5);
+}